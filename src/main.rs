@@ -1,7 +1,7 @@
 
 use anyhow::Result;
 use dioxus::prelude::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 
 // Module declarations
@@ -16,7 +16,12 @@ use types::*;
 use pdf::*;
 use ai::*;
 use storage::*;
+use ui::components::link_preview::*;
+use ui::components::outline::*;
+use ui::components::page_organizer::*;
 use ui::components::popups::*;
+use ui::components::search::*;
+use ui::components::thumbnails::*;
 
 
 fn main() -> Result<()> {
@@ -27,7 +32,9 @@ fn main() -> Result<()> {
             dioxus_desktop::tao::window::WindowBuilder::new()
                 .with_title("PDF Viewer in Rust")
                 .with_inner_size(dioxus_desktop::tao::dpi::LogicalSize::new(1200.0, 800.0))
-        );
+        )
+        // レンダリング済みのページ画像はデータURLではなくカスタムスキームで配信する
+        .with_custom_protocol(PAGE_IMAGE_SCHEME.to_string(), handle_page_image_request);
     dioxus_desktop::launch::launch(app, vec![], config);
     
     Ok(())
@@ -54,7 +61,8 @@ fn app() -> Element {
     let mut search_query = use_signal(|| String::new());
     let mut search_result = use_signal(|| String::new());
     let mut is_searching = use_signal(|| false);
-    let mut page_cache = use_signal(|| HashMap::<usize, PdfPageData>::new());
+    let viewer_settings = use_signal(|| load_viewer_settings());
+    let mut page_cache = use_signal(|| PageCache::new(viewer_settings.peek().page_cache_budget_bytes()));
     let mut is_loading = use_signal(|| false);
     let mut error_message = use_signal(|| String::new());
     let mut loaded_pdf_path = use_signal(|| -> Option<PathBuf> { None }); // 読み込み済みのPDFパスを追跡
@@ -86,18 +94,291 @@ fn app() -> Element {
     let mut show_markers_popup = use_signal(|| false);
     let mut marker_mode = use_signal(|| false); // マーカー配置モード
     
+    // サムネイルサイドバーと目次パネルの表示状態
+    let mut show_thumbnails = use_signal(|| true);
+    let mut show_outline = use_signal(|| false);
+    
+    // 文書内検索関連の状態管理
+    let mut show_document_search = use_signal(|| false);
+    let search_matches = use_signal(|| Vec::<SearchMatch>::new());
+    let active_match = use_signal(|| -> Option<usize> { None });
+    
+    // 文書のプロパティの表示状態
+    let mut show_properties_popup = use_signal(|| false);
+    
+    // 全ての文書を対象にした横断検索の表示状態
+    let mut show_library_search_popup = use_signal(|| false);
+    
+    // ページ整理モードの表示状態
+    let mut show_page_organizer = use_signal(|| false);
+    
+    // 回転を反映したコピーを保存中かどうか
+    let mut is_saving_rotated_copy = use_signal(|| false);
+    let mut rotated_copy_progress = use_signal(|| None::<(usize, usize)>); // (書き込んだページ数, 回転するページ数)
+    
+    // PDFの結合・分割ダイアログの表示状態
+    let mut show_merge_split_popup = use_signal(|| false);
+    
     // 単語帳リストをメモ化（新しいものが上）
     let flashcard_list = use_memo(move || flashcards());
     let recent_files_list = use_memo(move || recent_files());
     
+    // パスワードが入力されるたびに更新し、文書の読み込みをやり直す
+    let mut password_attempts = use_signal(|| 0u32);
+    
     // PDFファイル情報の取得（PDFが選択されている場合のみ）
-    let (total_pages, pdf_info) = use_memo(move || {
-        if let Some(path) = pdf_path() {
-            pdf::get_pdf_info(&path.to_string_lossy()).unwrap_or((0, "PDFの読み込みに失敗しました".to_string()))
-        } else {
-            (0, "PDFファイルが選択されていません".to_string())
+    // PDFiumの処理は所有スレッドで行い、UIは結果を待たずに描画を続ける
+    let document_info_resource = use_resource(move || async move {
+        let _ = password_attempts();
+        let Some(path) = pdf_path() else {
+            return Err(DocumentLoadError::NotSelected);
+        };
+        pdf::get_pdf_info(&path.to_string_lossy()).await.map_err(|e| {
+            if is_password_error(&e) {
+                DocumentLoadError::PasswordRequired
+            } else {
+                DocumentLoadError::Failed
+            }
+        })
+    });
+    let document_info = use_memo(move || -> Result<DocumentInfo, DocumentLoadError> {
+        document_info_resource.read().clone().unwrap_or(Err(DocumentLoadError::Loading))
+    });
+    
+    // 最近開いたファイルの表示名を、読み込んだ文書のタイトルに合わせる
+    use_effect(move || {
+        let Ok(info) = document_info() else { return };
+        let Some(path) = pdf_path.peek().clone() else { return };
+        if let Ok(true) = set_recent_file_title(&path.to_string_lossy(), info.title) {
+            recent_files.set(load_recent_files());
+        }
+    });
+    // タイトルが設定されていない文書はファイル名（拡張子なし）を表示する
+    let pdf_info_memo = use_memo(move || match document_info() {
+        Ok(info) => {
+            let title = info.title.unwrap_or_else(|| {
+                pdf_path()
+                    .and_then(|path| path.file_stem().map(|stem| stem.to_string_lossy().to_string()))
+                    .unwrap_or_default()
+            });
+            (info.page_count, title)
+        }
+        Err(error) => (0, error.message().to_string()),
+    });
+    let (total_pages, pdf_info) = pdf_info_memo();
+    
+    // 文書のアウトライン（目次）
+    let outline_resource = use_resource(move || async move {
+        // 文書を開けてから読み込む（パスワード入力後にも読み込み直す）
+        if document_info().is_err() {
+            return Vec::new();
+        }
+        match pdf_path() {
+            Some(path) => get_document_outline(&path.to_string_lossy()).await.unwrap_or_default(),
+            None => Vec::new(),
+        }
+    });
+    let outline = use_memo(move || outline_resource.read().clone().unwrap_or_default());
+    
+    // 各ページの元の寸法（未レンダリングページのプレースホルダー表示用）
+    let page_sizes_resource = use_resource(move || async move {
+        // 文書を開けてから読み込む（パスワード入力後にも読み込み直す）
+        if document_info().is_err() {
+            return Vec::new();
+        }
+        match pdf_path() {
+            Some(path) => get_page_sizes(&path.to_string_lossy()).await.unwrap_or_default(),
+            None => Vec::new(),
+        }
+    });
+    let page_sizes = use_memo(move || page_sizes_resource.read().clone().unwrap_or_default());
+    
+    // 表示中のページとレンダリングの世代管理
+    let mut current_page = use_signal(|| 0usize);
+    let mut render_generation = use_signal(|| 0u64); // ファイルを開くたびに更新し、古い結果の混入を防ぐ
+    let mut requested_pages = use_signal(|| HashMap::<usize, f32>::new()); // ページ -> 要求中のレンダリング倍率
+    
+    // ズーム関連の状態管理
+    let mut zoom_level = use_signal(|| ZoomLevel::FitWidth);
+    let mut viewport = use_signal(ViewportSize::default);
+    
+    // 大きなページのタイル表示関連の状態管理
+    let mut visible_regions = use_signal(|| HashMap::<usize, (f32, f32, f32, f32)>::new()); // ページ -> 表示範囲（左, 上, 右, 下の割合）
+    let mut tile_cache = use_signal(|| HashMap::<TileKey, PdfTileData>::new());
+    let mut requested_tiles = use_signal(|| HashSet::<TileKey>::new());
+    
+    // リンク先プレビュー関連の状態管理
+    let mut link_preview = use_signal(|| -> Option<LinkPreview> { None });
+    let mut link_preview_cache = use_signal(|| HashMap::<(usize, RotationAngle, Option<u32>, Option<u32>), PageImage>::new()); // (ページ, 回転, 位置) -> プレビュー画像
+    
+    // 回転を考慮したページの寸法（ポイント単位）
+    let page_dimensions = move |page_idx: usize| -> (f32, f32) {
+        let (width, height) = page_sizes.read().get(page_idx).copied().unwrap_or((612.0, 792.0));
+        match page_rotations.read().get(&page_idx).copied().unwrap_or(RotationAngle::None) {
+            RotationAngle::Rotate90 | RotationAngle::Rotate270 => (height, width),
+            _ => (width, height),
+        }
+    };
+    
+    // 現在のズーム倍率でのページの表示幅（CSSピクセル）
+    let page_display_width = move |page_idx: usize| -> f32 {
+        let (width, height) = page_dimensions(page_idx);
+        zoom_level().display_width(width, height, viewport())
+    };
+    
+    // スクロール位置から表示中のページを、ウィンドウサイズからビューアー領域の大きさを検出
+    use_future(move || async move {
+        let mut visible_page_eval = eval(r#"
+            let scheduled = false;
+            const reportViewport = () => {
+                const viewer = document.querySelector('.pdf-viewer');
+                const width = viewer ? viewer.clientWidth : window.innerWidth;
+                const height = viewer ? viewer.clientHeight : window.innerHeight;
+                dioxus.send({ viewport: [width, height, window.devicePixelRatio || 1] });
+            };
+            const report = () => {
+                scheduled = false;
+                // サイドバーの開閉などでビューアー領域の大きさだけが変わる場合もある
+                reportViewport();
+                const viewer = document.querySelector('.pdf-viewer');
+                if (!viewer) return;
+                const viewerRect = viewer.getBoundingClientRect();
+                const centerY = viewerRect.top + viewerRect.height / 2;
+                let nearestPage = -1;
+                let nearestDistance = Infinity;
+                const visible = [];
+                for (const element of viewer.querySelectorAll('[id^="page-wrapper-"]')) {
+                    const rect = element.getBoundingClientRect();
+                    const page = parseInt(element.id.substring('page-wrapper-'.length));
+                    const distance = Math.abs((rect.top + rect.bottom) / 2 - centerY);
+                    if (distance < nearestDistance) {
+                        nearestDistance = distance;
+                        nearestPage = page;
+                    }
+                    // ページのうち画面に見えている範囲を割合で通知（タイル表示用）
+                    if (rect.width > 0 && rect.height > 0 && rect.right > viewerRect.left && rect.left < viewerRect.right
+                        && rect.bottom > viewerRect.top && rect.top < viewerRect.bottom) {
+                        visible.push([
+                            page,
+                            (Math.max(rect.left, viewerRect.left) - rect.left) / rect.width,
+                            (Math.max(rect.top, viewerRect.top) - rect.top) / rect.height,
+                            (Math.min(rect.right, viewerRect.right) - rect.left) / rect.width,
+                            (Math.min(rect.bottom, viewerRect.bottom) - rect.top) / rect.height,
+                        ]);
+                    }
+                }
+                if (nearestPage >= 0) dioxus.send({ page: nearestPage, visible: visible });
+            };
+            const scheduleReport = () => {
+                if (!scheduled) {
+                    scheduled = true;
+                    requestAnimationFrame(report);
+                }
+            };
+            document.addEventListener('scroll', scheduleReport, true);
+            window.addEventListener('resize', reportViewport);
+            // ビューアー領域はPDFを開いたときに生成されるため、大きさの変化を監視する
+            new ResizeObserver(reportViewport).observe(document.body);
+            // ズームやページの読み込みでスクロールせずにレイアウトが変わった場合も表示範囲を再計算
+            new MutationObserver(scheduleReport).observe(document.body, { childList: true, subtree: true, attributes: true, attributeFilter: ['style'] });
+            reportViewport();
+        "#);
+        
+        while let Ok(value) = visible_page_eval.recv().await {
+            if let Some(page) = value.get("page").and_then(|page| page.as_u64()) {
+                let page = page as usize;
+                if *current_page.peek() != page {
+                    current_page.set(page);
+                }
+            }
+            if let Some(visible) = value.get("visible").and_then(|visible| visible.as_array()) {
+                let mut regions = HashMap::new();
+                for entry in visible.iter().filter_map(|entry| entry.as_array()) {
+                    let numbers: Vec<f64> = entry.iter().filter_map(|v| v.as_f64()).collect();
+                    if numbers.len() == 5 {
+                        regions.insert(numbers[0] as usize, (numbers[1] as f32, numbers[2] as f32, numbers[3] as f32, numbers[4] as f32));
+                    }
+                }
+                // 表示されたページを最近見たページとして記録（キャッシュから破棄される順序に使う）
+                for page_idx in regions.keys() {
+                    page_cache.peek().touch(*page_idx);
+                }
+                if *visible_regions.peek() != regions {
+                    visible_regions.set(regions);
+                }
+            }
+            if let Some(size) = value.get("viewport").and_then(|size| size.as_array()) {
+                let size: Vec<f32> = size.iter().filter_map(|v| v.as_f64()).map(|v| v as f32).collect();
+                if size.len() == 3 {
+                    let new_viewport = ViewportSize {
+                        width: size[0],
+                        height: size[1],
+                        device_pixel_ratio: size[2],
+                    };
+                    if *viewport.peek() != new_viewport {
+                        viewport.set(new_viewport);
+                    }
+                }
+            }
         }
-    })();
+    });
+    
+    // テキストレイヤーのダブルクリックで、辞書による単語の境界に合わせて選択し直す
+    // （日本語や中国語は空白で区切られないため、WebViewの既定の単語選択では範囲が合わない）
+    use_future(move || async move {
+        let mut double_click_eval = eval(r#"
+            document.addEventListener('dblclick', (event) => {
+                const span = event.target.closest ? event.target.closest('.selectable-text') : null;
+                if (!span || !span.firstChild) return;
+                let node = null;
+                let offset = 0;
+                if (document.caretRangeFromPoint) {
+                    const range = document.caretRangeFromPoint(event.clientX, event.clientY);
+                    if (range) { node = range.startContainer; offset = range.startOffset; }
+                } else if (document.caretPositionFromPoint) {
+                    const position = document.caretPositionFromPoint(event.clientX, event.clientY);
+                    if (position) { node = position.offsetNode; offset = position.offset; }
+                }
+                if (node !== span.firstChild) return;
+                // UTF-16の位置を文字数に変換
+                const charOffset = Array.from(node.textContent.slice(0, offset)).length;
+                dioxus.send({ page: parseInt(span.dataset.page), textIdx: parseInt(span.dataset.textIdx), offset: charOffset });
+            });
+        "#);
+        
+        while let Ok(value) = double_click_eval.recv().await {
+            let (Some(page_idx), Some(text_idx), Some(offset)) = (
+                value.get("page").and_then(|v| v.as_u64()),
+                value.get("textIdx").and_then(|v| v.as_u64()),
+                value.get("offset").and_then(|v| v.as_u64()),
+            ) else { continue };
+            let Some(text) = page_cache.peek()
+                .get(&(page_idx as usize))
+                .and_then(|page_data| page_data.text_elements.get(text_idx as usize))
+                .map(|element| element.text.clone()) else { continue };
+            let Some(word) = word_at(&text, offset as usize) else { continue };
+            
+            // 選択した語をAI検索の検索語句にする
+            search_query.set(text.chars().skip(word.start).take(word.len()).collect());
+            
+            eval(&format!(
+                r#"
+                const span = document.querySelector('.selectable-text[data-page="{}"][data-text-idx="{}"]');
+                if (span && span.firstChild) {{
+                    const node = span.firstChild;
+                    const chars = Array.from(node.textContent);
+                    const range = document.createRange();
+                    range.setStart(node, chars.slice(0, {}).join('').length);
+                    range.setEnd(node, chars.slice(0, {}).join('').length);
+                    const selection = window.getSelection();
+                    selection.removeAllRanges();
+                    selection.addRange(range);
+                }}
+                "#,
+                page_idx, text_idx, word.start, word.end
+            ));
+        }
+    });
     
     // PDFが選択されたときの読み込み処理（新しいファイルの場合のみ）
     use_effect(move || {
@@ -105,14 +386,22 @@ fn app() -> Element {
             // 新しいファイルかどうかチェック
             let should_load = loaded_pdf_path().as_ref() != Some(&path);
             
-            if total_pages > 0 && !is_loading() && should_load {
-                is_loading.set(true);
+            if pdf_info_memo().0 > 0 && should_load {
+                // 新しい世代を開始（以前のファイルの要求はキャンセルされ、結果も破棄される）
+                let generation = *render_generation.peek() + 1;
+                render_generation.set(generation);
+                requested_pages.write().clear();
                 page_cache.write().clear(); // 既存のキャッシュをクリア
+                requested_tiles.write().clear();
+                tile_cache.write().clear();
+                link_preview.set(None);
+                link_preview_cache.write().clear();
+                current_page.set(0);
                 error_message.set(String::new());
                 
                 // 該当PDFの回転状態を読み込み
                 let rotations = load_page_rotations(&path.to_string_lossy());
-                page_rotations.set(rotations.clone());
+                page_rotations.set(rotations);
                 
                 // 該当PDFのブックマークを読み込み
                 let bookmark = load_reading_bookmark(&path.to_string_lossy());
@@ -122,73 +411,141 @@ fn app() -> Element {
                 let markers = load_position_markers(&path.to_string_lossy());
                 position_markers.set(markers);
                 
-                spawn(async move {
-                    // 最初の3ページを最優先で読み込み
-                    for page_idx in 0..3.min(total_pages) {
-                        let rotation = rotations.get(&page_idx).copied().unwrap_or(RotationAngle::None);
-                        if let Ok(page_data) = render_pdf_page_with_text(&path.to_string_lossy(), page_idx, rotation) {
-                            // 混入チェック: ページデータのインデックスが正しいか確認
-                            if page_data.page_index != page_idx {
-                                eprintln!("CRITICAL: Page data contamination detected in priority load! Expected page {}, got page {}", page_idx, page_data.page_index);
-                                continue; // 混入したデータは破棄
-                            }
-                            page_cache.write().insert(page_idx, page_data);
-                        }
-                    }
+                loaded_pdf_path.set(Some(path)); // 読み込み済みのパスを記録
+            }
+        }
+    });
+    
+    // 表示中のページを中心にレンダリング要求を発行（近いページから優先的に処理される）
+    // ズーム倍率やウィンドウサイズが変わった場合は、表示範囲のページを新しい倍率で再レンダリングする
+    use_effect(move || {
+        let generation = render_generation();
+        let focus_page = current_page();
+        let _ = visible_regions(); // スクロールのたびに、キャッシュから破棄されたページがないか確認する
+        let zoom = zoom_level();
+        let viewport_size = viewport();
+        let total_pages = pdf_info_memo().0;
+        let Some(path) = loaded_pdf_path() else { return };
+        if total_pages == 0 {
+            return;
+        }
+        
+        let window_start = focus_page.saturating_sub(RENDER_WINDOW_BEHIND);
+        let window_end = (focus_page + RENDER_WINDOW_AHEAD + 1).min(total_pages);
+        
+        // 表示範囲から外れた要求はワーカー側でキャンセルされる
+        set_render_focus(generation, focus_page, window_start..window_end);
+        
+        for page_idx in window_start..window_end {
+            let (page_width, page_height) = page_dimensions(page_idx);
+            let scale = zoom.render_scale(page_width, page_height, viewport_size);
+            
+            // 同じ倍率でレンダリング済み、または要求中のページはスキップ
+            let is_cached = page_cache.peek().get(&page_idx).map_or(false, |page_data| page_data.render_scale == scale);
+            let is_requested = requested_pages.peek().get(&page_idx) == Some(&scale);
+            if is_cached || is_requested {
+                continue;
+            }
+            
+            requested_pages.write().insert(page_idx, scale);
+            is_loading.set(true);
+            
+            let path_str = path.to_string_lossy().to_string();
+            let rotation = page_rotations.peek().get(&page_idx).copied().unwrap_or(RotationAngle::None);
+            spawn(async move {
+                let result = render_page_in_background(&path_str, page_idx, rotation, scale, generation).await;
                 
-                // 残りのページを並列で読み込み（CPUコア数に基づいてバッチサイズを決定）
-                let chunk_size = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4).min(8);
-                for chunk_start in (3..total_pages).step_by(chunk_size) {
-                    let chunk_end = (chunk_start + chunk_size).min(total_pages);
-                    let batch_futures: Vec<_> = (chunk_start..chunk_end)
-                        .map(|page_idx| {
-                            let path_clone = path.clone();
-                            let rotation = rotations.get(&page_idx).copied().unwrap_or(RotationAngle::None);
-                            Box::pin(async move {
-                                match render_pdf_page_with_text(&path_clone.to_string_lossy(), page_idx, rotation) {
-                                    Ok(data) => Some((page_idx, data)),
-                                    Err(_) => None,
-                                }
-                            })
-                        })
-                        .collect();
-                    
-                    // バッチを並列実行
-                    let results = futures::future::join_all(batch_futures).await;
-                    for result in results {
-                        if let Some((page_idx, page_data)) = result {
-                            // 混入チェック: ページデータのインデックスが正しいか確認
-                            if page_data.page_index != page_idx {
-                                eprintln!("CRITICAL: Page data contamination detected! Expected page {}, got page {}", page_idx, page_data.page_index);
-                                continue; // 混入したデータは破棄
-                            }
-                            page_cache.write().insert(page_idx, page_data);
-                        }
+                // 別のファイルを開いた後に届いた古い世代の結果は破棄
+                if *render_generation.peek() != generation {
+                    return;
+                }
+                
+                // 後から別の倍率で要求し直されている場合は、未表示のときだけ暫定的に反映する
+                let is_latest_request = requested_pages.peek().get(&page_idx) == Some(&scale);
+                if is_latest_request {
+                    requested_pages.write().remove(&page_idx);
+                }
+                if let Ok(page_data) = result {
+                    // 混入チェック: ページデータのインデックスが正しいか確認
+                    if page_data.page_index != page_idx {
+                        eprintln!("CRITICAL: Page data contamination detected! Expected page {}, got page {}", page_idx, page_data.page_index);
+                    } else if is_latest_request || !page_cache.peek().contains_key(&page_idx) {
+                        page_cache.write().insert(page_idx, page_data);
                     }
-                    
-                    // 少し待機してUIの応答性を保つ
-                    tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+                }
+                is_loading.set(!requested_pages.peek().is_empty());
+            });
+        }
+    });
+
+    // 大きすぎて1枚の画像にできないページは、見えている範囲のタイルだけを高解像度でレンダリング
+    use_effect(move || {
+        let generation = render_generation();
+        let regions = visible_regions();
+        let zoom = zoom_level();
+        let viewport_size = viewport();
+        let Some(path) = loaded_pdf_path() else { return };
+        
+        let mut needed_tiles = HashSet::new();
+        for (&page_idx, &visible) in regions.iter() {
+            let (page_width, page_height) = page_dimensions(page_idx);
+            let scale = zoom.render_scale(page_width, page_height, viewport_size);
+            if !needs_tiled_rendering(page_width, page_height, scale) {
+                continue;
+            }
+            
+            let rotation = page_rotations.peek().get(&page_idx).copied().unwrap_or(RotationAngle::None);
+            for region in visible_tile_regions(page_width, page_height, scale, visible) {
+                let key = TileKey::new(page_idx, rotation, scale, region);
+                needed_tiles.insert(key);
+                if tile_cache.peek().contains_key(&key) || requested_tiles.peek().contains(&key) {
+                    continue;
                 }
                 
-                    is_loading.set(false);
-                    loaded_pdf_path.set(Some(path)); // 読み込み完了したパスを記録
+                requested_tiles.write().insert(key);
+                let path_str = path.to_string_lossy().to_string();
+                spawn(async move {
+                    let result = render_tile_in_background(&path_str, page_idx, rotation, scale, region, generation).await;
+                    if *render_generation.peek() != generation {
+                        return;
+                    }
+                    requested_tiles.write().remove(&key);
+                    if let Ok(tile_data) = result {
+                        tile_cache.write().insert(key, tile_data);
+                    }
                 });
             }
         }
+        
+        // 見えていないタイルは上限を超えた分から破棄し、メモリ使用量を表示範囲に比例させる
+        let cached_count = tile_cache.peek().len();
+        if cached_count > MAX_CACHED_TILES {
+            let focus_page = *current_page.peek();
+            let mut hidden_tiles: Vec<TileKey> = tile_cache.peek().keys().filter(|key| !needed_tiles.contains(key)).copied().collect();
+            hidden_tiles.sort_by_key(|key| std::cmp::Reverse(key.page_index.abs_diff(focus_page)));
+            let mut tile_cache = tile_cache.write();
+            for key in hidden_tiles.into_iter().take(cached_count - MAX_CACHED_TILES) {
+                tile_cache.remove(&key);
+            }
+        }
+        // 見えなくなったタイルの要求は結果を待たない
+        requested_tiles.write().retain(|key| needed_tiles.contains(key));
     });
 
-    // レンダリング済みページのリストを取得
-    let rendered_pages = use_memo(move || {
+    // 全ページのスロット（レンダリング済みならページデータ、未レンダリングならNone）
+    let page_slots = use_memo(move || {
+        let total_pages = pdf_info_memo().0;
         let mut pages = Vec::new();
         for page_idx in 0..total_pages {
-            if let Some(page_data) = page_cache().get(&page_idx) {
+            let page_data = page_cache.read().get(&page_idx).cloned().filter(|page_data| {
                 // 混入チェック: ページインデックスが一致するか確認
                 if page_data.page_index != page_idx {
                     println!("WARNING: Page data mismatch detected! Expected page {}, got page {}", page_idx, page_data.page_index);
-                    continue; // 混入したページはスキップ
+                    return false; // 混入したページはスキップ
                 }
-                pages.push((page_idx, page_data.clone()));
-            }
+                true
+            });
+            pages.push((page_idx, page_data));
         }
         pages
     });
@@ -225,6 +582,28 @@ fn app() -> Element {
                             },
                             {if marker_mode() { "📍 マーカーモード: ON" } else { "📍 マーカーモード" }}
                         }
+                        button {
+                            class: "thumbnails-toggle-btn",
+                            style: {
+                                let bg_color = if show_thumbnails() { "#16a085" } else { "#34495e" };
+                                format!("padding: 8px 16px; background-color: {}; color: white; border: none; border-radius: 4px; cursor: pointer;", bg_color)
+                            },
+                            onclick: move |_| {
+                                show_thumbnails.set(!show_thumbnails());
+                            },
+                            "🖼 サムネイル"
+                        }
+                        button {
+                            class: "outline-toggle-btn",
+                            style: {
+                                let bg_color = if show_outline() { "#16a085" } else { "#34495e" };
+                                format!("padding: 8px 16px; background-color: {}; color: white; border: none; border-radius: 4px; cursor: pointer;", bg_color)
+                            },
+                            onclick: move |_| {
+                                show_outline.set(!show_outline());
+                            },
+                            "📑 目次"
+                        }
                         button {
                             class: "markers-list-btn",
                             style: "padding: 8px 16px; background-color: #e67e22; color: white; border: none; border-radius: 4px; cursor: pointer;",
@@ -241,6 +620,22 @@ fn app() -> Element {
                             },
                             "📋 最近のファイル"
                         }
+                        button {
+                            class: "library-search-btn",
+                            style: "padding: 8px 16px; background-color: #2980b9; color: white; border: none; border-radius: 4px; cursor: pointer;",
+                            onclick: move |_| {
+                                show_library_search_popup.set(true);
+                            },
+                            "🗂 全文書を検索"
+                        }
+                        button {
+                            class: "merge-split-btn",
+                            style: "padding: 8px 16px; background-color: #8e44ad; color: white; border: none; border-radius: 4px; cursor: pointer;",
+                            onclick: move |_| {
+                                show_merge_split_popup.set(true);
+                            },
+                            "📚 結合・分割"
+                        }
                         button {
                             class: "file-select-btn",
                             style: "padding: 8px 16px; background-color: #3498db; color: white; border: none; border-radius: 4px; cursor: pointer;",
@@ -255,6 +650,9 @@ fn app() -> Element {
                                         let selected_path = file_handle.path().to_path_buf();
                                         let _ = add_recent_file(selected_path.to_string_lossy().to_string(), selected_path.file_name().unwrap_or_default().to_string_lossy().to_string());
                                         recent_files.set(load_recent_files());
+                                        if let Some(previous_path) = pdf_path() {
+                                            release_pdf_document(&previous_path.to_string_lossy());
+                                        }
                                         pdf_path.set(Some(selected_path));
                                         page_cache.write().clear();
                                         loaded_pdf_path.set(None); // 新しいファイル選択時にリセット
@@ -291,10 +689,80 @@ fn app() -> Element {
                                 },
                                 "🔄 全て回転"
                             }
+                            button {
+                                class: "page-organizer-btn",
+                                style: "padding: 8px 16px; background-color: #8e44ad; color: white; border: none; border-radius: 4px; cursor: pointer;",
+                                onclick: move |_| {
+                                    show_page_organizer.set(true);
+                                },
+                                "🗃 ページ整理"
+                            }
+                            button {
+                                class: "save-rotated-copy-btn",
+                                style: "padding: 8px 16px; background-color: #27ae60; color: white; border: none; border-radius: 4px; cursor: pointer;",
+                                disabled: is_saving_rotated_copy(),
+                                onclick: move |_| {
+                                    let Some(path) = pdf_path() else { return };
+                                    if is_saving_rotated_copy() {
+                                        return;
+                                    }
+                                    spawn(async move {
+                                        let file_stem = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+                                        let mut dialog = rfd::AsyncFileDialog::new()
+                                            .add_filter("PDF files", &["pdf"])
+                                            .set_title("回転を反映したコピーを保存")
+                                            .set_file_name(format!("{}_rotated.pdf", file_stem));
+                                        if let Some(directory) = path.parent() {
+                                            dialog = dialog.set_directory(directory);
+                                        }
+                                        let Some(file_handle) = dialog.save_file().await else { return };
+                                        
+                                        is_saving_rotated_copy.set(true);
+                                        rotated_copy_progress.set(None);
+                                        let rotations = page_rotations.peek().clone();
+                                        let result = save_rotated_copy(&path.to_string_lossy(), &rotations, file_handle.path(), |done, total| {
+                                            rotated_copy_progress.set(Some((done, total)));
+                                        })
+                                        .await;
+                                        is_saving_rotated_copy.set(false);
+                                        rotated_copy_progress.set(None);
+                                        match result {
+                                            Ok(()) => {
+                                                rfd::AsyncMessageDialog::new()
+                                                    .set_title("保存しました")
+                                                    .set_description(format!("回転を反映したコピーを保存しました。\n\n{}", file_handle.path().display()))
+                                                    .show()
+                                                    .await;
+                                            }
+                                            Err(e) => error_message.set(format!("コピーを保存できませんでした: {}", e)),
+                                        }
+                                    });
+                                },
+                                if is_saving_rotated_copy() {
+                                    {match rotated_copy_progress() {
+                                        Some((done, total)) => format!("💾 保存中… ({}/{})", done, total),
+                                        None => "💾 保存中…".to_string(),
+                                    }}
+                                } else {
+                                    "💾 回転を保存したコピー…"
+                                }
+                            }
+                            button {
+                                class: "properties-btn",
+                                style: "padding: 8px 16px; background-color: #7f8c8d; color: white; border: none; border-radius: 4px; cursor: pointer;",
+                                onclick: move |_| {
+                                    show_properties_popup.set(true);
+                                },
+                                "ℹ️ プロパティ"
+                            }
                             button {
                                 class: "file-close-btn",
                                 style: "padding: 8px 16px; background-color: #e74c3c; color: white; border: none; border-radius: 4px; cursor: pointer;",
                                 onclick: move |_| {
+                                    // ドキュメントのハンドルを解放
+                                    if let Some(path) = pdf_path() {
+                                        release_pdf_document(&path.to_string_lossy());
+                                    }
                                     pdf_path.set(None);
                                     page_cache.write().clear();
                                     loaded_pdf_path.set(None); // ファイル閉じる時にもリセット
@@ -319,10 +787,73 @@ fn app() -> Element {
                     div { class: "controls",
                         style: "margin-bottom: 15px; padding: 10px; background-color: #f8f9fa; border-radius: 4px; display: flex; align-items: center; gap: 10px;",
                         span { class: "page-info",
-                            if is_loading() {
-                                "全 {total_pages} ページ読み込み中... ({rendered_pages().len()}/{total_pages})"
-                            } else {
-                                "全 {total_pages} ページ読み込み完了"
+                            "ページ {current_page() + 1} / {total_pages}"
+                        }
+                        
+                        button {
+                            class: "document-search-btn",
+                            style: {
+                                let bg_color = if show_document_search() { "#16a085" } else { "#34495e" };
+                                format!("padding: 5px 10px; background-color: {}; color: white; border: none; border-radius: 3px; cursor: pointer;", bg_color)
+                            },
+                            onclick: move |_| {
+                                show_document_search.set(!show_document_search());
+                            },
+                            "🔍 文書内検索"
+                        }
+                        
+                        div {
+                            class: "zoom-controls",
+                            style: "display: flex; align-items: center; gap: 5px; margin-left: auto;",
+                            button {
+                                class: "zoom-out-btn",
+                                style: "padding: 5px 10px; background-color: #34495e; color: white; border: none; border-radius: 3px; cursor: pointer;",
+                                onclick: move |_| {
+                                    let (page_width, page_height) = page_dimensions(current_page());
+                                    zoom_level.set(zoom_level().zoom_out(page_width, page_height, viewport()));
+                                },
+                                "－"
+                            }
+                            select {
+                                value: match zoom_level() {
+                                    ZoomLevel::FitWidth => "fit-width".to_string(),
+                                    ZoomLevel::FitPage => "fit-page".to_string(),
+                                    ZoomLevel::Percent(percent) => percent.to_string(),
+                                },
+                                onchange: move |evt| {
+                                    match evt.value().as_str() {
+                                        "fit-width" => zoom_level.set(ZoomLevel::FitWidth),
+                                        "fit-page" => zoom_level.set(ZoomLevel::FitPage),
+                                        value => {
+                                            if let Ok(percent) = value.parse::<u32>() {
+                                                zoom_level.set(ZoomLevel::Percent(percent));
+                                            }
+                                        }
+                                    }
+                                },
+                                style: "padding: 5px; border-radius: 3px; border: 1px solid #bdc3c7;",
+                                option { value: "fit-width", "幅に合わせる" }
+                                option { value: "fit-page", "ページ全体" }
+                                for step in ZOOM_STEPS.iter() {
+                                    option { key: "{step}", value: "{step}", "{step}%" }
+                                }
+                            }
+                            button {
+                                class: "zoom-in-btn",
+                                style: "padding: 5px 10px; background-color: #34495e; color: white; border: none; border-radius: 3px; cursor: pointer;",
+                                onclick: move |_| {
+                                    let (page_width, page_height) = page_dimensions(current_page());
+                                    zoom_level.set(zoom_level().zoom_in(page_width, page_height, viewport()));
+                                },
+                                "＋"
+                            }
+                            span {
+                                class: "zoom-percent",
+                                style: "min-width: 50px; text-align: right; font-size: 12px; color: #7f8c8d;",
+                                {
+                                    let (page_width, page_height) = page_dimensions(current_page());
+                                    format!("{}%", zoom_level().effective_percent(page_width, page_height, viewport()))
+                                }
                             }
                         }
                         
@@ -355,225 +886,447 @@ fn app() -> Element {
                             "{pdf_info}"
                         }
                     } else {
+                        if show_outline() {
+                            outline_panel {
+                                outline: outline,
+                                current_page: current_page,
+                            }
+                        }
+                        if show_thumbnails() {
+                            thumbnail_sidebar {
+                                loaded_pdf_path: loaded_pdf_path,
+                                total_pages: total_pages,
+                                current_page: current_page,
+                                page_sizes: page_sizes,
+                                page_rotations: page_rotations,
+                                current_bookmark: current_bookmark,
+                                position_markers: position_markers,
+                                render_generation: render_generation,
+                            }
+                        }
                         div { 
                             class: "pdf-section",
                             style: "flex: 1; display: flex; flex-direction: column; overflow: hidden; height: 100%;",
                             
+                            if show_document_search() {
+                                document_search_panel {
+                                    loaded_pdf_path: loaded_pdf_path,
+                                    render_generation: render_generation,
+                                    page_sizes: page_sizes,
+                                    page_rotations: page_rotations,
+                                    search_matches: search_matches,
+                                    active_match: active_match,
+                                }
+                            }
+                            
                             div { 
                                 class: "pdf-viewer",
-                                style: "flex: 1; display: flex; flex-direction: column; overflow-y: auto; overflow-x: hidden; padding: 10px; gap: 20px; height: 100%; max-height: calc(100vh - 200px);",
-                                for (page_idx, page_data) in rendered_pages.read().iter() {
-                                    div {
-                                        key: "{page_idx}",
-                                        class: "page-container",
-                                        style: "display: flex; flex-direction: column; align-items: center;",
+                                style: "flex: 1; display: flex; flex-direction: column; overflow-y: auto; overflow-x: auto; padding: 10px; gap: 20px; height: 100%; max-height: calc(100vh - 200px);",
+                                for (page_idx, page_slot) in page_slots.read().iter() {
+                                    if let Some(page_data) = page_slot {
                                         div {
-                                            class: "page-header",
-                                            style: "display: flex; align-items: center; justify-content: center; gap: 10px; margin-bottom: 10px;",
+                                            key: "{page_idx}",
+                                            class: "page-container",
+                                            style: "display: flex; flex-direction: column; align-items: safe center;",
                                             div {
-                                                class: "page-number",
-                                                style: "font-weight: bold; color: #2c3e50;",
-                                                "ページ {page_idx + 1}"
-                                            }
-                                            button {
-                                                class: "rotate-page-btn",
-                                                style: "padding: 5px 10px; background-color: #3498db; color: white; border: none; border-radius: 3px; cursor: pointer; font-size: 12px;",
-                                                onclick: {
-                                                    let page_idx = *page_idx;
-                                                    move |_| {
-                                                        if let Some(path) = pdf_path() {
-                                                            // 現在の回転状態を取得
-                                                            let current_rotation = page_rotations().get(&page_idx).copied().unwrap_or(RotationAngle::None);
-                                                            let new_rotation = current_rotation.next();
+                                                class: "page-header",
+                                                style: "display: flex; align-items: center; justify-content: center; gap: 10px; margin-bottom: 10px;",
+                                                div {
+                                                    class: "page-number",
+                                                    style: "font-weight: bold; color: #2c3e50;",
+                                                    "ページ {page_idx + 1}"
+                                                }
+                                                button {
+                                                    class: "rotate-page-btn",
+                                                    style: "padding: 5px 10px; background-color: #3498db; color: white; border: none; border-radius: 3px; cursor: pointer; font-size: 12px;",
+                                                    onclick: {
+                                                        let page_idx = *page_idx;
+                                                        move |_| {
+                                                            if let Some(path) = pdf_path() {
+                                                                // 現在の回転状態を取得
+                                                                let current_rotation = page_rotations().get(&page_idx).copied().unwrap_or(RotationAngle::None);
+                                                                let new_rotation = current_rotation.next();
+                                                            
+                                                                // 回転状態を更新
+                                                                page_rotations.write().insert(page_idx, new_rotation);
                                                             
-                                                            // 回転状態を更新
-                                                            page_rotations.write().insert(page_idx, new_rotation);
+                                                                // 回転状態を保存
+                                                                let _ = save_page_rotations(&path.to_string_lossy(), page_rotations());
+                                                            
+                                                                // ページを再レンダリング
+                                                                let (page_width, page_height) = page_dimensions(page_idx);
+                                                                let scale = zoom_level().render_scale(page_width, page_height, viewport());
+                                                                let path_clone = path.clone();
+                                                                let generation = *render_generation.peek();
+                                                                spawn(async move {
+                                                                    let result = render_page_in_background(&path_clone.to_string_lossy(), page_idx, new_rotation, scale, generation).await;
+                                                                    
+                                                                    // 別のファイルを開いた後や、さらに回転した後に届いた古い結果は破棄
+                                                                    let is_current_rotation = page_rotations.peek().get(&page_idx).copied().unwrap_or(RotationAngle::None) == new_rotation;
+                                                                    if *render_generation.peek() != generation || !is_current_rotation {
+                                                                        return;
+                                                                    }
+                                                                    if let Ok(page_data) = result {
+                                                                        page_cache.write().insert(page_idx, page_data);
+                                                                    }
+                                                                });
+                                                            }
+                                                        }
+                                                    },
+                                                    "🔄"
+                                                }
+                                                button {
+                                                    class: "bookmark-page-btn",
+                                                    style: {
+                                                        let is_bookmarked = current_bookmark().map_or(false, |b| b.current_page == *page_idx);
+                                                        let bg_color = if is_bookmarked { "#f39c12" } else { "#95a5a6" };
+                                                        format!("padding: 5px 10px; background-color: {}; color: white; border: none; border-radius: 3px; cursor: pointer; font-size: 12px;", bg_color)
+                                                    },
+                                                    onclick: {
+                                                        let page_idx = *page_idx;
+                                                        move |_| {
+                                                            if let Some(path) = pdf_path() {
+                                                                let path_str = path.to_string_lossy().to_string();
                                                             
-                                                            // 回転状態を保存
-                                                            let _ = save_page_rotations(&path.to_string_lossy(), page_rotations());
+                                                                // 現在のブックマーク状態をチェック
+                                                                let is_currently_bookmarked = current_bookmark()
+                                                                    .map_or(false, |b| b.current_page == page_idx);
                                                             
-                                                            // ページを再レンダリング
-                                                            let path_clone = path.clone();
-                                                            spawn(async move {
-                                                                if let Ok(page_data) = render_pdf_page_with_text(&path_clone.to_string_lossy(), page_idx, new_rotation) {
-                                                                    page_cache.write().insert(page_idx, page_data);
+                                                                if is_currently_bookmarked {
+                                                                    // ブックマークが既に存在する場合は削除
+                                                                    let _ = delete_reading_bookmark(&path_str);
+                                                                    current_bookmark.set(None);
+                                                                } else {
+                                                                    // ブックマークが存在しない場合は新規作成
+                                                                    let bookmark = ReadingBookmark {
+                                                                        pdf_path: path_str.clone(),
+                                                                        current_page: page_idx,
+                                                                        total_pages,
+                                                                        last_read_time: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+                                                                        reading_progress: (page_idx + 1) as f32 / total_pages as f32,
+                                                                    };
+                                                                    let _ = save_reading_bookmark(bookmark.clone());
+                                                                    current_bookmark.set(Some(bookmark));
                                                                 }
-                                                            });
+                                                            
+                                                                // マーカー状態を更新
+                                                                let markers = load_position_markers(&path_str);
+                                                                position_markers.set(markers);
+                                                            }
                                                         }
-                                                    }
-                                                },
-                                                "🔄"
+                                                    },
+                                                    "🔖"
+                                                }
                                             }
-                                            button {
-                                                class: "bookmark-page-btn",
-                                                style: {
-                                                    let is_bookmarked = current_bookmark().map_or(false, |b| b.current_page == *page_idx);
-                                                    let bg_color = if is_bookmarked { "#f39c12" } else { "#95a5a6" };
-                                                    format!("padding: 5px 10px; background-color: {}; color: white; border: none; border-radius: 3px; cursor: pointer; font-size: 12px;", bg_color)
-                                                },
+                                            div {
+                                                class: "page-wrapper",
+                                                id: "page-wrapper-{page_idx}",
+                                                style: format!("position: relative; display: block; width: {}px; flex-shrink: 0; margin-bottom: 20px; isolation: isolate; container-type: inline-size; cursor: {};", page_display_width(*page_idx), if marker_mode() { "crosshair" } else { "default" }),
                                                 onclick: {
                                                     let page_idx = *page_idx;
-                                                    move |_| {
-                                                        if let Some(path) = pdf_path() {
-                                                            let path_str = path.to_string_lossy().to_string();
-                                                            
-                                                            // 現在のブックマーク状態をチェック
-                                                            let is_currently_bookmarked = current_bookmark()
-                                                                .map_or(false, |b| b.current_page == page_idx);
+                                                    move |evt| {
+                                                        if marker_mode() {
+                                                            if let Some(path) = pdf_path() {
+                                                                // クリック位置を要素内の相対座標で取得
+                                                                let coords = evt.data().element_coordinates();
                                                             
-                                                            if is_currently_bookmarked {
-                                                                // ブックマークが既に存在する場合は削除
-                                                                let _ = delete_reading_bookmark(&path_str);
-                                                                current_bookmark.set(None);
-                                                            } else {
-                                                                // ブックマークが存在しない場合は新規作成
-                                                                let bookmark = ReadingBookmark {
-                                                                    pdf_path: path_str.clone(),
-                                                                    current_page: page_idx,
-                                                                    total_pages,
-                                                                    last_read_time: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string(),
-                                                                    reading_progress: (page_idx + 1) as f32 / total_pages as f32,
+                                                                // 実際のPDFページデータから正確な寸法と回転を取得
+                                                                let (actual_width, actual_height, rotation) = if let Some(page_data) = page_cache.read().get(&page_idx) {
+                                                                    (page_data.page_width, page_data.page_height, page_data.rotation)
+                                                                } else {
+                                                                    (1000.0, 1294.0, RotationAngle::None) // デフォルト値
                                                                 };
-                                                                let _ = save_reading_bookmark(bookmark.clone());
-                                                                current_bookmark.set(Some(bookmark));
-                                                            }
                                                             
-                                                            // マーカー状態を更新
-                                                            let markers = load_position_markers(&path_str);
-                                                            position_markers.set(markers);
-                                                        }
-                                                    }
-                                                },
-                                                "🔖"
-                                            }
-                                        }
-                                        div {
-                                            class: "page-wrapper",
-                                            id: "page-wrapper-{page_idx}",
-                                            style: format!("position: relative; display: block; width: 100%; max-width: 800px; margin-bottom: 20px; isolation: isolate; cursor: {};", if marker_mode() { "crosshair" } else { "default" }),
-                                            onclick: {
-                                                let page_idx = *page_idx;
-                                                move |evt| {
-                                                    if marker_mode() {
-                                                        if let Some(path) = pdf_path() {
-                                                            // クリック位置を要素内の相対座標で取得
-                                                            let coords = evt.data().element_coordinates();
-                                                            
-                                                            // 実際のPDFページデータから正確な寸法を取得
-                                                            let (actual_width, actual_height) = if let Some(page_data) = page_cache().get(&page_idx) {
-                                                                (page_data.page_width, page_data.page_height)
-                                                            } else {
-                                                                (1000.0, 1294.0) // デフォルト値
-                                                            };
+                                                                // 表示されている要素の幅はズーム倍率で決まり、高さはアスペクト比を保持
+                                                                let display_width = page_display_width(page_idx) as f64;
+                                                                let display_height = display_width * (actual_height as f64 / actual_width as f64);
                                                             
-                                                            // 表示されている要素の幅は最大800pxで、高さはアスペクト比を保持
-                                                            let display_width = 800.0f64;
-                                                            let display_height = display_width * (actual_height as f64 / actual_width as f64);
+                                                                let x = coords.x / display_width;
+                                                                let y = coords.y / display_height;
                                                             
-                                                            let x = coords.x / display_width;
-                                                            let y = coords.y / display_height;
+                                                                // 範囲を0.0-1.0にクランプ
+                                                                let x = x.max(0.0).min(1.0);
+                                                                let y = y.max(0.0).min(1.0);
                                                             
-                                                            // 範囲を0.0-1.0にクランプ
-                                                            let x = x.max(0.0).min(1.0);
-                                                            let y = y.max(0.0).min(1.0);
+                                                                // マーカーを保存（ページを回転しても同じ位置を指すよう、回転前の座標で保存）
+                                                                if let Some(path) = pdf_path() {
+                                                                    let (x, y) = rotation.unrotate_fraction(x as f32, y as f32);
+                                                                    let marker = PositionMarker::new(page_idx, x, y, String::new());
+                                                                    let _ = save_position_marker(&path.to_string_lossy(), marker);
+                                                                }
                                                             
-                                                            // マーカーを保存
-                                                            if let Some(path) = pdf_path() {
-                                                                let marker = PositionMarker::new(page_idx, x as f32, y as f32, String::new());
-                                                                let _ = save_position_marker(&path.to_string_lossy(), marker);
+                                                                // マーカー状態を更新
+                                                                let markers = load_position_markers(&path.to_string_lossy());
+                                                                position_markers.set(markers);
                                                             }
-                                                            
-                                                            // マーカー状態を更新
-                                                            let markers = load_position_markers(&path.to_string_lossy());
-                                                            position_markers.set(markers);
                                                         }
                                                     }
+                                                },
+                                                img {
+                                                    src: "{page_data.image_data}",
+                                                    alt: "PDF Page {page_idx + 1}",
+                                                    class: "pdf-page",
+                                                    style: "display: block; width: 100%; height: auto; border: 1px solid #ddd; border-radius: 4px; box-shadow: 0 4px 8px rgba(0, 0, 0, 0.1); background-color: white;"
                                                 }
-                                            },
-                                            img {
-                                                src: "{page_data.image_data}",
-                                                alt: "PDF Page {page_idx + 1}",
-                                                class: "pdf-page",
-                                                style: "display: block; width: 100%; height: auto; border: 1px solid #ddd; border-radius: 4px; box-shadow: 0 4px 8px rgba(0, 0, 0, 0.1); background-color: white;"
-                                            }
-                                            div {
-                                                class: "text-overlay",
-                                                id: "text-overlay-{page_idx}",
-                                                style: "position: absolute; top: 0; left: 0; right: 0; bottom: 0; pointer-events: none; border-radius: 4px; z-index: 1; overflow: hidden;",
-                                                for (text_idx, text_elem) in page_data.text_elements.iter().enumerate() {
-                                                    span {
-                                                        key: "p{page_idx}t{text_idx}",
-                                                        class: "selectable-text",
-                                                        "data-page": "{page_idx}",
-                                                        "data-text-idx": "{text_idx}",
-                                                        style: "position: absolute; 
-                                                               left: {text_elem.bounds.x / page_data.page_width * 100.0}%; 
-                                                               top: {text_elem.bounds.y / page_data.page_height * 100.0}%;
-                                                               width: {text_elem.bounds.width / page_data.page_width * 100.0}%;
-                                                               height: {text_elem.bounds.height / page_data.page_height * 100.0}%;
-                                                               font-size: {(text_elem.font_size / page_data.page_height * 100.0).max(0.8)}%;
-                                                               color: transparent;
-                                                               pointer-events: auto;
-                                                               user-select: text;
-                                                               cursor: text;
-                                                               font-family: monospace;
-                                                               line-height: 1;
-                                                               overflow: hidden;
-                                                               white-space: nowrap;
-                                                               z-index: 2;",
-                                                        "{text_elem.text} "
+                                                if needs_tiled_rendering(page_data.page_width, page_data.page_height, page_data.render_scale) {
+                                                    div {
+                                                        class: "tile-layer",
+                                                        style: "position: absolute; top: 0; left: 0; right: 0; bottom: 0; pointer-events: none; overflow: hidden; border-radius: 4px;",
+                                                        for tile in tile_cache.read().values().filter(|tile| tile.key.page_index == *page_idx && tile.key.rotation == page_data.rotation && tile.key.scale_key == TileKey::scale_key(page_data.render_scale)) {
+                                                            img {
+                                                                key: "{tile.key.region.x}-{tile.key.region.y}",
+                                                                src: "{tile.image_data}",
+                                                                style: "position: absolute; left: {tile.key.region.x as f32 / tile.full_width as f32 * 100.0}%; top: {tile.key.region.y as f32 / tile.full_height as f32 * 100.0}%; width: {tile.key.region.width as f32 / tile.full_width as f32 * 100.0}%; height: {tile.key.region.height as f32 / tile.full_height as f32 * 100.0}%;"
+                                                            }
+                                                        }
                                                     }
                                                 }
-                                            }
-                                            div {
-                                                class: "marker-overlay",
-                                                style: "position: absolute; top: 0; left: 0; right: 0; bottom: 0; pointer-events: none; z-index: 3;",
-                                                for marker in position_markers().iter().filter(|m| m.page_index == *page_idx) {
-                                                    div {
-                                                        key: "marker-{marker.id}",
-                                                        class: "position-marker",
-                                                        style: "position: absolute; 
-                                                               left: {marker.x * 100.0}%; 
-                                                               top: {marker.y * 100.0}%; 
-                                                               width: 12px; 
-                                                               height: 12px; 
-                                                               background-color: #e74c3c; 
-                                                               border: 2px solid white; 
-                                                               border-radius: 50%; 
-                                                               transform: translate(-50%, -50%); 
-                                                               cursor: pointer; 
-                                                               pointer-events: auto; 
-                                                               z-index: 4; 
-                                                               box-shadow: 0 2px 4px rgba(0,0,0,0.3);",
-                                                        onclick: {
-                                                            let marker_id = marker.id.clone();
-                                                            move |evt| {
-                                                                evt.stop_propagation();
-                                                                if let Some(path) = pdf_path() {
-                                                                    // マーカーを削除
-                                                                    if let Some(path) = pdf_path() {
-                                                                        let _ = delete_position_marker(&path.to_string_lossy(), &marker_id);
+                                                div {
+                                                    class: "search-highlight-overlay",
+                                                    style: "position: absolute; top: 0; left: 0; right: 0; bottom: 0; pointer-events: none; z-index: 1;",
+                                                    for (match_idx, search_match) in search_matches.read().iter().enumerate().filter(|(_, search_match)| search_match.page_index == *page_idx) {
+                                                        // 検索結果は回転前の座標のため、ページの回転に合わせて変換
+                                                        for (rect_idx, rect) in search_match.rects.iter().map(|rect| page_data.rotate_bounds(rect)).enumerate() {
+                                                            div {
+                                                                key: "m{match_idx}r{rect_idx}",
+                                                                style: format!(
+                                                                    "position: absolute; left: {}%; top: {}%; width: {}%; height: {}%; background-color: {}; mix-blend-mode: multiply; border-radius: 2px;",
+                                                                    rect.x / page_data.page_width * 100.0,
+                                                                    rect.y / page_data.page_height * 100.0,
+                                                                    rect.width / page_data.page_width * 100.0,
+                                                                    rect.height / page_data.page_height * 100.0,
+                                                                    // 選択中の一致箇所は濃い色で強調
+                                                                    if active_match() == Some(match_idx) { "rgba(255, 140, 0, 0.6)" } else { "rgba(255, 230, 0, 0.5)" }
+                                                                ),
+                                                            }
+                                                        }
+                                                    }
+                                                }
+                                                div {
+                                                    class: "text-overlay",
+                                                    id: "text-overlay-{page_idx}",
+                                                    style: "position: absolute; top: 0; left: 0; right: 0; bottom: 0; pointer-events: none; border-radius: 4px; z-index: 1; overflow: hidden;",
+                                                    for (text_idx, text_elem) in page_data.text_elements.iter().enumerate() {
+                                                        span {
+                                                            key: "p{page_idx}t{text_idx}",
+                                                            class: "selectable-text",
+                                                            "data-page": "{page_idx}",
+                                                            "data-text-idx": "{text_idx}",
+                                                            // 回転前の向きの大きさで中心に配置し、ページと同じ角度だけ回転させる
+                                                            style: "position: absolute; 
+                                                                   left: {(text_elem.bounds.x + text_elem.bounds.width / 2.0) / page_data.page_width * 100.0}%; 
+                                                                   top: {(text_elem.bounds.y + text_elem.bounds.height / 2.0) / page_data.page_height * 100.0}%;
+                                                                   width: {text_elem.bounds.unrotated_size(page_data.rotation).0 / page_data.page_width * 100.0}%;
+                                                                   height: {text_elem.bounds.unrotated_size(page_data.rotation).1 / page_data.page_height * 100.0}%;
+                                                                   transform: translate(-50%, -50%) rotate({page_data.rotation.to_degrees()}deg);
+                                                                   font-size: {text_elem.font_size / page_data.page_width * 100.0}cqw;
+                                                                   color: transparent;
+                                                                   pointer-events: auto;
+                                                                   user-select: text;
+                                                                   cursor: text;
+                                                                   font-family: monospace;
+                                                                   writing-mode: {text_elem.writing_mode()};
+                                                                   line-height: 1;
+                                                                   overflow: hidden;
+                                                                   white-space: nowrap;
+                                                                   z-index: 2;",
+                                                            "{text_elem.text} "
+                                                        }
+                                                    }
+                                                }
+                                                div {
+                                                    class: "link-overlay",
+                                                    id: "link-overlay-{page_idx}",
+                                                    style: "position: absolute; top: 0; left: 0; right: 0; bottom: 0; pointer-events: none; z-index: 3;",
+                                                    for (link_idx, link) in page_data.links.iter().enumerate() {
+                                                        div {
+                                                            key: "p{page_idx}l{link_idx}",
+                                                            class: "pdf-link",
+                                                            title: match &link.target {
+                                                                LinkTarget::Page { page_index, .. } => format!("ページ {} へ移動", page_index + 1),
+                                                                LinkTarget::Uri(uri) => uri.clone(),
+                                                            },
+                                                            style: format!(
+                                                                "position: absolute; left: {}%; top: {}%; width: {}%; height: {}%; cursor: pointer; pointer-events: {};",
+                                                                link.bounds.x / page_data.page_width * 100.0,
+                                                                link.bounds.y / page_data.page_height * 100.0,
+                                                                link.bounds.width / page_data.page_width * 100.0,
+                                                                link.bounds.height / page_data.page_height * 100.0,
+                                                                // マーカーモード中はページのクリックを優先する
+                                                                if marker_mode() { "none" } else { "auto" }
+                                                            ),
+                                                            onmouseenter: {
+                                                                let target = link.target.clone();
+                                                                move |e: Event<MouseData>| {
+                                                                    let LinkTarget::Page { page_index, y, x } = target.clone() else { return };
+                                                                    let Some(path) = loaded_pdf_path() else { return };
+                                                                    let coords = e.data().client_coordinates();
+                                                                    // 移動先のページを表示と同じ向きで切り出す
+                                                                    let rotation = page_rotations.peek().get(&page_index).copied().unwrap_or(RotationAngle::None);
+                                                                    let cache_key = (page_index, rotation, x.map(|x| x.round() as u32), y.map(|y| y.round() as u32));
+                                                                    let cached_image = link_preview_cache.peek().get(&cache_key).cloned();
+                                                                    let needs_render = cached_image.is_none();
+                                                                    link_preview.set(Some(LinkPreview {
+                                                                        page_index,
+                                                                        x,
+                                                                        y,
+                                                                        rotation,
+                                                                        client_x: coords.x,
+                                                                        client_y: coords.y,
+                                                                        image: cached_image,
+                                                                    }));
+                                                                    if !needs_render {
+                                                                        return;
                                                                     }
                                                                     
-                                                                    // マーカー状態を更新
-                                                                    let markers = load_position_markers(&path.to_string_lossy());
-                                                                    position_markers.set(markers);
+                                                                    let generation = render_generation();
+                                                                    spawn(async move {
+                                                                        let result = render_link_preview_in_background(&path.to_string_lossy(), page_index, rotation, x, y, generation).await;
+                                                                        if *render_generation.peek() != generation {
+                                                                            return;
+                                                                        }
+                                                                        if let Ok(image) = result {
+                                                                            // 直近のプレビューだけを保持する
+                                                                            if link_preview_cache.peek().len() >= 32 {
+                                                                                link_preview_cache.write().clear();
+                                                                            }
+                                                                            link_preview_cache.write().insert(cache_key, image.clone());
+                                                                            
+                                                                            // まだ同じリンクにマウスが重なっている場合だけ表示を更新
+                                                                            let mut link_preview = link_preview.write();
+                                                                            if let Some(preview) = link_preview.as_mut().filter(|preview| {
+                                                                                preview.page_index == page_index && preview.x == x && preview.y == y && preview.rotation == rotation
+                                                                            }) {
+                                                                                preview.image = Some(image);
+                                                                            }
+                                                                        }
+                                                                    });
                                                                 }
-                                                            }
-                                                        },
-                                                        title: "クリックして削除"
+                                                            },
+                                                            onmouseleave: move |_| {
+                                                                link_preview.set(None);
+                                                            },
+                                                            onclick: {
+                                                                let target = link.target.clone();
+                                                                move |e: Event<MouseData>| {
+                                                                    e.stop_propagation();
+                                                                    link_preview.set(None);
+                                                                    match target.clone() {
+                                                                        LinkTarget::Page { page_index, y, x } => {
+                                                                            // 移動先の位置をページの高さに対する割合で指定してスクロール
+                                                                            let (page_width, page_height) = page_sizes.read().get(page_index).copied().unwrap_or((612.0, 792.0));
+                                                                            let rotation = page_rotations.read().get(&page_index).copied().unwrap_or(RotationAngle::None);
+                                                                            let fraction = if x.is_none() && y.is_none() {
+                                                                                0.0
+                                                                            } else {
+                                                                                // 移動先の点（xの指定がなければ左右の中央）を回転後の表示上の割合に変換し、縦方向の位置を使う
+                                                                                let x_fraction = x.map_or(0.5, |x| x / page_width);
+                                                                                let y_fraction = y.map_or(0.0, |y| y / page_height);
+                                                                                let (_, displayed_y) = rotation.rotate_fraction(x_fraction, y_fraction);
+                                                                                displayed_y.clamp(0.0, 1.0)
+                                                                            };
+                                                                            eval(&format!(
+                                                                                r#"
+                                                                                const viewer = document.querySelector('.pdf-viewer');
+                                                                                const element = document.getElementById('page-wrapper-{}');
+                                                                                if (viewer && element) {{
+                                                                                    const rect = element.getBoundingClientRect();
+                                                                                    const viewerRect = viewer.getBoundingClientRect();
+                                                                                    viewer.scrollBy({{ top: rect.top - viewerRect.top + rect.height * {}, behavior: 'smooth' }});
+                                                                                }}
+                                                                                "#,
+                                                                                page_index, fraction
+                                                                            ));
+                                                                        }
+                                                                        LinkTarget::Uri(uri) => {
+                                                                            // 外部リンクは確認してからブラウザで開く
+                                                                            spawn(async move {
+                                                                                let confirmed = rfd::AsyncMessageDialog::new()
+                                                                                    .set_title("外部リンクを開く")
+                                                                                    .set_description(format!("次のリンクをブラウザで開きますか？\n\n{}", uri))
+                                                                                    .set_buttons(rfd::MessageButtons::YesNo)
+                                                                                    .show()
+                                                                                    .await;
+                                                                                if confirmed == rfd::MessageDialogResult::Yes {
+                                                                                    if let Err(e) = open_external_link(&uri) {
+                                                                                        error_message.set(format!("リンクを開けませんでした: {}", e));
+                                                                                    }
+                                                                                }
+                                                                            });
+                                                                        }
+                                                                    }
+                                                                }
+                                                            },
+                                                        }
+                                                    }
+                                                }
+                                                div {
+                                                    class: "marker-overlay",
+                                                    style: "position: absolute; top: 0; left: 0; right: 0; bottom: 0; pointer-events: none; z-index: 3;",
+                                                    for marker in position_markers().iter().filter(|m| m.page_index == *page_idx) {
+                                                        div {
+                                                            key: "marker-{marker.id}",
+                                                            class: "position-marker",
+                                                            style: "position: absolute; 
+                                                                   left: {marker.displayed_position(page_data.rotation).0 * 100.0}%; 
+                                                                   top: {marker.displayed_position(page_data.rotation).1 * 100.0}%; 
+                                                                   width: 12px; 
+                                                                   height: 12px; 
+                                                                   background-color: #e74c3c; 
+                                                                   border: 2px solid white; 
+                                                                   border-radius: 50%; 
+                                                                   transform: translate(-50%, -50%); 
+                                                                   cursor: pointer; 
+                                                                   pointer-events: auto; 
+                                                                   z-index: 4; 
+                                                                   box-shadow: 0 2px 4px rgba(0,0,0,0.3);",
+                                                            onclick: {
+                                                                let marker_id = marker.id.clone();
+                                                                move |evt| {
+                                                                    evt.stop_propagation();
+                                                                    if let Some(path) = pdf_path() {
+                                                                        // マーカーを削除
+                                                                        if let Some(path) = pdf_path() {
+                                                                            let _ = delete_position_marker(&path.to_string_lossy(), &marker_id);
+                                                                        }
+                                                                    
+                                                                        // マーカー状態を更新
+                                                                        let markers = load_position_markers(&path.to_string_lossy());
+                                                                        position_markers.set(markers);
+                                                                    }
+                                                                }
+                                                            },
+                                                            title: "クリックして削除"
+                                                        }
                                                     }
                                                 }
                                             }
                                         }
-                                    }
-                                }
-                                
-                                if is_loading() && rendered_pages.read().len() < total_pages {
-                                    div {
-                                        class: "loading-pages-placeholder",
-                                        style: "text-align: center; padding: 40px; color: #3498db; font-style: italic; border: 2px dashed #3498db; border-radius: 8px; background-color: #ecf0f1;",
-                                        "残り {total_pages - rendered_pages.read().len()} ページを読み込み中..."
+                                    } else {
+                                        // 未レンダリングのページは元の寸法で枠だけ表示（スクロール位置を保つため）
+                                        div {
+                                            key: "{page_idx}",
+                                            class: "page-container",
+                                            style: "display: flex; flex-direction: column; align-items: safe center;",
+                                            div {
+                                                class: "page-header",
+                                                style: "display: flex; align-items: center; justify-content: center; gap: 10px; margin-bottom: 10px;",
+                                                div {
+                                                    class: "page-number",
+                                                    style: "font-weight: bold; color: #2c3e50;",
+                                                    "ページ {page_idx + 1}"
+                                                }
+                                            }
+                                            div {
+                                                class: "page-wrapper page-placeholder",
+                                                id: "page-wrapper-{page_idx}",
+                                                style: {
+                                                    let (width, height) = page_dimensions(*page_idx);
+                                                    format!("position: relative; display: flex; align-items: center; justify-content: center; width: {}px; flex-shrink: 0; aspect-ratio: {} / {}; margin-bottom: 20px; border: 1px solid #ddd; border-radius: 4px; background-color: #ecf0f1; color: #3498db; font-style: italic;", page_display_width(*page_idx), width, height)
+                                                },
+                                                "読み込み中..."
+                                            }
+                                        }
                                     }
                                 }
                             }
@@ -834,5 +1587,57 @@ fn app() -> Element {
                 is_loading: is_loading,
             }
         }
+        
+        // ページ整理モード（読み込みが終わった文書のみ）
+        if show_page_organizer() && loaded_pdf_path().is_some() {
+            page_organizer {
+                show_page_organizer: show_page_organizer,
+                loaded_pdf_path: loaded_pdf_path,
+                page_sizes: page_sizes,
+                page_rotations: page_rotations,
+                render_generation: render_generation,
+            }
+        }
+        
+        // 全ての文書を対象にした検索のポップアップ
+        if show_library_search_popup() {
+            library_search_popup {
+                show_library_search_popup: show_library_search_popup,
+                pdf_path: pdf_path,
+                page_cache: page_cache,
+                loaded_pdf_path: loaded_pdf_path,
+                is_loading: is_loading,
+                recent_files: recent_files,
+                render_generation: render_generation,
+            }
+        }
+        
+        // PDFの結合・分割のポップアップ
+        if show_merge_split_popup() {
+            merge_split_popup {
+                show_merge_split_popup: show_merge_split_popup,
+                pdf_path: pdf_path,
+            }
+        }
+        
+        if pdf_path().is_some() && document_info() == Err(DocumentLoadError::PasswordRequired) {
+            password_prompt_popup {
+                pdf_path: pdf_path,
+                password_attempts: password_attempts,
+                loaded_pdf_path: loaded_pdf_path,
+            }
+        }
+        
+        link_preview_popup {
+            link_preview: link_preview,
+        }
+        
+        if show_properties_popup() {
+            document_properties_popup {
+                show_properties_popup: show_properties_popup,
+                document_info: document_info,
+                pdf_path: pdf_path,
+            }
+        }
     }
 }
@@ -0,0 +1,187 @@
+use std::cell::Cell;
+use std::collections::HashMap;
+use crate::types::PdfPageData;
+
+struct CachedPage {
+    data: PdfPageData,
+    size: usize,
+    last_viewed: Cell<u64>,
+}
+
+// レンダリング済みページのキャッシュ（メモリ使用量の上限を超えたら最も長く見られていないページから破棄）
+// 破棄されたページはスクロールで表示範囲に戻ったときに再レンダリングされる
+pub struct PageCache {
+    pages: HashMap<usize, CachedPage>,
+    budget_bytes: usize,
+    used_bytes: usize,
+    clock: Cell<u64>,
+}
+
+impl PageCache {
+    pub fn new(budget_bytes: usize) -> Self {
+        Self {
+            pages: HashMap::new(),
+            budget_bytes,
+            used_bytes: 0,
+            clock: Cell::new(0),
+        }
+    }
+
+    pub fn get(&self, page_index: &usize) -> Option<&PdfPageData> {
+        self.pages.get(page_index).map(|page| &page.data)
+    }
+
+    pub fn contains_key(&self, page_index: &usize) -> bool {
+        self.pages.contains_key(page_index)
+    }
+
+    pub fn insert(&mut self, page_index: usize, data: PdfPageData) {
+        let size = estimate_page_size(&data);
+        if let Some(previous) = self.pages.remove(&page_index) {
+            self.used_bytes -= previous.size;
+        }
+
+        self.used_bytes += size;
+        self.pages.insert(page_index, CachedPage {
+            data,
+            size,
+            last_viewed: Cell::new(self.tick()),
+        });
+
+        self.evict_over_budget(page_index);
+    }
+
+    // ページが画面に表示されたことを記録
+    // 再描画を起こさないよう共有参照から更新できるようにしている（Signal::peekで呼び出す）
+    pub fn touch(&self, page_index: usize) {
+        if let Some(page) = self.pages.get(&page_index) {
+            page.last_viewed.set(self.tick());
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.pages.clear();
+        self.used_bytes = 0;
+    }
+
+    fn tick(&self) -> u64 {
+        let now = self.clock.get() + 1;
+        self.clock.set(now);
+        now
+    }
+
+    // 予算を超えている間、最も長く見られていないページを破棄（keepで指定したページは残す）
+    fn evict_over_budget(&mut self, keep: usize) {
+        while self.used_bytes > self.budget_bytes {
+            let oldest = self
+                .pages
+                .iter()
+                .filter(|(&page_index, _)| page_index != keep)
+                .min_by_key(|(_, page)| page.last_viewed.get())
+                .map(|(&page_index, _)| page_index);
+
+            match oldest.and_then(|page_index| self.pages.remove(&page_index)) {
+                Some(page) => self.used_bytes -= page.size,
+                None => break,
+            }
+        }
+    }
+}
+
+// ページデータが占めるおおよそのメモリ量（pdfimage://で配信するPNGのデータとテキスト要素）
+fn estimate_page_size(data: &PdfPageData) -> usize {
+    let text_size: usize = data
+        .text_elements
+        .iter()
+        .map(|element| std::mem::size_of_val(element) + element.text.len())
+        .sum();
+    std::mem::size_of::<PdfPageData>() + data.image_data.byte_len() + text_size
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::PageImage;
+    use crate::types::RotationAngle;
+
+    fn page(page_index: usize, image_bytes: usize) -> PdfPageData {
+        PdfPageData {
+            image_data: PageImage::new(vec![0; image_bytes]),
+            text_elements: Vec::new(),
+            page_width: 612.0,
+            page_height: 792.0,
+            page_index,
+            rotation: RotationAngle::None,
+            render_scale: 1.0,
+            links: Vec::new(),
+        }
+    }
+
+    fn cached_pages(cache: &PageCache) -> Vec<usize> {
+        let mut pages: Vec<usize> = cache.pages.keys().copied().collect();
+        pages.sort();
+        pages
+    }
+
+    // 同じ大きさのページがちょうど3枚入る予算のキャッシュ
+    fn cache_for_three_pages() -> PageCache {
+        PageCache::new(estimate_page_size(&page(0, 1000)) * 3)
+    }
+
+    #[test]
+    fn keeps_pages_within_budget() {
+        let mut cache = cache_for_three_pages();
+        for page_index in 0..3 {
+            cache.insert(page_index, page(page_index, 1000));
+        }
+        assert_eq!(cached_pages(&cache), [0, 1, 2]);
+        assert_eq!(cache.used_bytes, cache.budget_bytes);
+
+        cache.insert(3, page(3, 1000));
+        assert_eq!(cached_pages(&cache), [1, 2, 3]);
+        assert!(cache.used_bytes <= cache.budget_bytes);
+    }
+
+    #[test]
+    fn evicts_least_recently_viewed_page_first() {
+        let mut cache = cache_for_three_pages();
+        for page_index in 0..3 {
+            cache.insert(page_index, page(page_index, 1000));
+        }
+
+        // 最初に読み込んだページを見直すと、次に古い1ページ目が破棄される
+        cache.touch(0);
+        cache.insert(3, page(3, 1000));
+        assert_eq!(cached_pages(&cache), [0, 2, 3]);
+
+        // 見直したページより前に見たページから破棄される
+        cache.touch(2);
+        cache.insert(4, page(4, 1000));
+        assert_eq!(cached_pages(&cache), [2, 3, 4]);
+    }
+
+    #[test]
+    fn large_page_evicts_several_pages_but_is_kept() {
+        let mut cache = cache_for_three_pages();
+        for page_index in 0..3 {
+            cache.insert(page_index, page(page_index, 1000));
+        }
+
+        // 予算より大きいページでも、挿入したページ自体は残す
+        cache.insert(9, page(9, cache.budget_bytes * 2));
+        assert_eq!(cached_pages(&cache), [9]);
+        assert_eq!(cache.used_bytes, estimate_page_size(&page(9, cache.budget_bytes * 2)));
+    }
+
+    #[test]
+    fn replacing_a_page_does_not_count_it_twice() {
+        let mut cache = cache_for_three_pages();
+        cache.insert(0, page(0, 1000));
+        cache.insert(0, page(0, 500));
+        assert_eq!(cache.used_bytes, estimate_page_size(&page(0, 500)));
+
+        cache.clear();
+        assert_eq!(cache.used_bytes, 0);
+        assert!(!cache.contains_key(&0));
+    }
+}
@@ -0,0 +1,273 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::sync::{mpsc, Mutex, OnceLock};
+use pdfium_render::prelude::*;
+use tokio::sync::oneshot;
+use crate::pdf::get_pdfium_library_path;
+
+// PDFiumのバインディングと開いているドキュメントを保持するサービス
+// pdfium-renderのthread_safe機能は、Pdfiumのインスタンスが存在する間ずっと全体のロックを保持するため、
+// インスタンスは所有スレッドで1つだけ作り、他のスレッドからの処理は全てこのスレッドに依頼する
+pub struct PdfDocumentService<'a> {
+    pdfium: &'a Pdfium,
+    documents: HashMap<String, PdfDocument<'a>>,
+    // 結合・分割などで複数の依頼にまたがって編集中の文書（EditingDocumentが破棄されると閉じる）
+    editing_documents: HashMap<u64, PdfDocument<'a>>,
+    next_editing_id: u64,
+}
+
+impl<'a> PdfDocumentService<'a> {
+    pub fn document(&mut self, pdf_path: &str) -> Result<&PdfDocument<'a>> {
+        if !self.documents.contains_key(pdf_path) {
+            let document = match document_password(pdf_path) {
+                // パスワードの寿命に縛られないよう、バイト列から読み込む
+                Some(password) => self.pdfium.load_pdf_from_byte_vec(std::fs::read(pdf_path)?, Some(&password))?,
+                None => self.pdfium.load_pdf_from_file(pdf_path, None)?,
+            };
+            self.documents.insert(pdf_path.to_string(), document);
+        }
+
+        Ok(&self.documents[pdf_path])
+    }
+
+    pub fn close(&mut self, pdf_path: &str) {
+        self.documents.remove(pdf_path);
+    }
+
+    // 新しい文書の作成などに使うバインディング
+    pub fn pdfium(&self) -> &'a Pdfium {
+        self.pdfium
+    }
+
+    // 編集用に、表示に使っているものとは別のハンドルとして読み込む（変更が表示中の文書に影響しないように）
+    pub fn load_for_editing(&self, pdf_path: &str) -> Result<PdfDocument<'a>> {
+        let password = document_password(pdf_path);
+        Ok(self.pdfium.load_pdf_from_byte_vec(std::fs::read(pdf_path)?, password.as_deref())?)
+    }
+
+    // 編集中の文書を所有スレッドに保持し、後の依頼から参照できるハンドルを返す
+    pub fn keep_for_editing(&mut self, document: PdfDocument<'a>) -> EditingDocument {
+        let id = self.next_editing_id;
+        self.next_editing_id += 1;
+        self.editing_documents.insert(id, document);
+        EditingDocument { id }
+    }
+
+    pub fn editing_document(&self, id: u64) -> Result<&PdfDocument<'a>> {
+        self.editing_documents
+            .get(&id)
+            .ok_or_else(|| anyhow::anyhow!("編集中の文書が見つかりません"))
+    }
+
+    // 編集中の文書を一時的に取り出して変更する（他の編集中の文書を参照しながら変更できるように）
+    pub fn modify_editing_document<R>(&mut self, id: u64, f: impl FnOnce(&mut PdfDocument<'a>, &Self) -> Result<R>) -> Result<R> {
+        let mut document = self
+            .editing_documents
+            .remove(&id)
+            .ok_or_else(|| anyhow::anyhow!("編集中の文書が見つかりません"))?;
+        let result = f(&mut document, self);
+        self.editing_documents.insert(id, document);
+        result
+    }
+}
+
+// 所有スレッドに保持された編集中の文書のハンドル（破棄すると文書も閉じる）
+pub struct EditingDocument {
+    id: u64,
+}
+
+impl EditingDocument {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+}
+
+impl Drop for EditingDocument {
+    fn drop(&mut self) {
+        let id = self.id;
+        send_request(boxed_request(move |service| {
+            service.editing_documents.remove(&id);
+        }));
+    }
+}
+
+// 入力されたパスワード（パス -> パスワード）
+// セッション中のみメモリ上に保持し、ディスクには保存しない
+static DOCUMENT_PASSWORDS: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+
+fn document_passwords() -> &'static Mutex<HashMap<String, String>> {
+    DOCUMENT_PASSWORDS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn document_password(pdf_path: &str) -> Option<String> {
+    document_passwords().lock().ok()?.get(pdf_path).cloned()
+}
+
+// パスワードを登録（全スレッドのドキュメント読み込みで使われる）
+pub fn set_document_password(pdf_path: &str, password: String) {
+    if let Ok(mut passwords) = document_passwords().lock() {
+        passwords.insert(pdf_path.to_string(), password);
+    }
+}
+
+// パスワードで保護されたドキュメントかどうか（ディスクキャッシュに復号した内容を残さないために使う）
+pub fn has_document_password(pdf_path: &str) -> bool {
+    document_password(pdf_path).is_some()
+}
+
+// 読み込みの失敗がパスワードの不足・誤りによるものかどうか
+pub fn is_password_error(error: &anyhow::Error) -> bool {
+    matches!(
+        error.downcast_ref::<PdfiumError>(),
+        Some(PdfiumError::PdfiumLibraryInternalError(PdfiumInternalError::PasswordError))
+    )
+}
+
+// 所有スレッドで実行する処理
+type PdfiumRequest = Box<dyn for<'a> FnOnce(&mut PdfDocumentService<'a>) + Send>;
+
+static PDFIUM_OWNER: OnceLock<Mutex<mpsc::Sender<PdfiumRequest>>> = OnceLock::new();
+// PDFiumを初期化できなかった理由（依頼した処理の失敗として呼び出し側に返す）
+static PDFIUM_INIT_ERROR: OnceLock<String> = OnceLock::new();
+
+fn pdfium_owner() -> &'static Mutex<mpsc::Sender<PdfiumRequest>> {
+    PDFIUM_OWNER.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<PdfiumRequest>();
+        let spawn_result = std::thread::Builder::new()
+            .name("pdfium-owner".to_string())
+            .spawn(move || pdfium_owner_loop(receiver));
+        if let Err(e) = spawn_result {
+            let _ = PDFIUM_INIT_ERROR.set(format!("PDFiumのスレッドを起動できませんでした: {}", e));
+        }
+        Mutex::new(sender)
+    })
+}
+
+// PDFiumを所有し、依頼された処理を届いた順に実行する
+fn pdfium_owner_loop(receiver: mpsc::Receiver<PdfiumRequest>) {
+    let bindings = get_pdfium_library_path().and_then(|library_path| Ok(Pdfium::bind_to_library(library_path)?));
+    let pdfium = match bindings {
+        Ok(bindings) => Pdfium::new(bindings),
+        Err(e) => {
+            // 依頼は実行せずに破棄する（呼び出し側にはこのエラーが返る）
+            let _ = PDFIUM_INIT_ERROR.set(e.to_string());
+            while receiver.recv().is_ok() {}
+            return;
+        }
+    };
+
+    let mut service = PdfDocumentService {
+        pdfium: &pdfium,
+        documents: HashMap::new(),
+        editing_documents: HashMap::new(),
+        next_editing_id: 0,
+    };
+    while let Ok(request) = receiver.recv() {
+        // 1つの処理のパニックで、以降の全ての処理が止まらないようにする
+        if std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| request(&mut service))).is_err() {
+            eprintln!("PDFiumの処理中にパニックが発生しました");
+        }
+    }
+}
+
+fn boxed_request<F>(f: F) -> PdfiumRequest
+where
+    F: for<'a> FnOnce(&mut PdfDocumentService<'a>) + Send + 'static,
+{
+    Box::new(f)
+}
+
+fn send_request(request: PdfiumRequest) {
+    if let Ok(sender) = pdfium_owner().lock() {
+        // 送信できない場合は処理が破棄され、呼び出し側の返信待ちがエラーになる
+        let _ = sender.send(request);
+    }
+}
+
+// 返信が届かなかった（PDFiumが使えない、または処理がパニックした）場合のエラー
+fn pdfium_unavailable_error() -> anyhow::Error {
+    match PDFIUM_INIT_ERROR.get() {
+        Some(message) => anyhow::anyhow!("{}", message),
+        None => anyhow::anyhow!("PDFiumの処理が中断されました"),
+    }
+}
+
+// PDFiumの所有スレッドで処理を実行し、完了を待つ（呼び出したスレッドはその間ブロックされる）
+// 所有スレッドで実行中の処理から呼び出すとデッドロックするため、処理の中では使わない
+pub fn with_pdfium<R, F>(f: F) -> Result<R>
+where
+    R: Send + 'static,
+    F: for<'a> FnOnce(&mut PdfDocumentService<'a>) -> Result<R> + Send + 'static,
+{
+    let (reply, receiver) = mpsc::sync_channel(1);
+    send_request(boxed_request(move |service| {
+        let _ = reply.send(f(service));
+    }));
+
+    receiver.recv().map_err(|_| pdfium_unavailable_error())?
+}
+
+// PDFiumの所有スレッドで処理を実行し、完了を非同期に待つ（UIの処理から使う）
+pub async fn with_pdfium_async<R, F>(f: F) -> Result<R>
+where
+    R: Send + 'static,
+    F: for<'a> FnOnce(&mut PdfDocumentService<'a>) -> Result<R> + Send + 'static,
+{
+    let (reply, receiver) = oneshot::channel();
+    send_request(boxed_request(move |service| {
+        let _ = reply.send(f(service));
+    }));
+
+    receiver.await.map_err(|_| pdfium_unavailable_error())?
+}
+
+// 開いたドキュメントをキャッシュから取得して処理を実行（未オープンの場合は読み込む）
+// 完了までブロックするため、ワーカースレッドから使い、コンポーネントやメモの中では使わない
+pub fn with_pdf_document<R, F>(pdf_path: &str, f: F) -> Result<R>
+where
+    R: Send + 'static,
+    F: FnOnce(&PdfDocument) -> Result<R> + Send + 'static,
+{
+    let pdf_path = pdf_path.to_string();
+    with_pdfium(move |service| f(service.document(&pdf_path)?))
+}
+
+// with_pdf_documentの非同期版（コンポーネントやリソースから使う）
+pub async fn with_pdf_document_async<R, F>(pdf_path: &str, f: F) -> Result<R>
+where
+    R: Send + 'static,
+    F: FnOnce(&PdfDocument) -> Result<R> + Send + 'static,
+{
+    let pdf_path = pdf_path.to_string();
+    with_pdfium_async(move |service| f(service.document(&pdf_path)?)).await
+}
+
+// ファイルを閉じたときにドキュメントのハンドルを解放（完了は待たない）
+pub fn close_pdf_document(pdf_path: &str) {
+    // まだ何も読み込んでいなければ、閉じるためだけにPDFiumを初期化しない
+    if PDFIUM_OWNER.get().is_none() {
+        return;
+    }
+
+    let pdf_path = pdf_path.to_string();
+    send_request(boxed_request(move |service| service.close(&pdf_path)));
+}
+
+// 編集用に読み込み、所有スレッドに保持する
+pub async fn load_pdf_for_editing(pdf_path: &str) -> Result<EditingDocument> {
+    let pdf_path = pdf_path.to_string();
+    with_pdfium_async(move |service| {
+        let document = service.load_for_editing(&pdf_path)?;
+        Ok(service.keep_for_editing(document))
+    })
+    .await
+}
+
+// 空の文書を作成し、所有スレッドに保持する
+pub async fn create_pdf_for_editing() -> Result<EditingDocument> {
+    with_pdfium_async(|service| {
+        let document = service.pdfium().create_new_pdf()?;
+        Ok(service.keep_for_editing(document))
+    })
+    .await
+}
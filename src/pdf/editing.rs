@@ -0,0 +1,619 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use pdfium_render::prelude::*;
+use crate::types::{OrganizerPage, OrganizerPageSource, RotationAngle, SplitMode};
+use crate::pdf::{
+    EditingDocument, bookmark_page_index, create_pdf_for_editing, has_document_password, load_pdf_for_editing, with_pdfium_async,
+};
+
+// 進捗を報告する間隔（大きな文書でもこのページ数ごとに進捗を知らせる）
+const COPY_CHUNK_PAGES: usize = 50;
+// 分割した各ファイルの名前に使うしおりの見出しの最大文字数
+const MAX_PART_NAME_CHARS: usize = 40;
+
+// 分割して書き出す1つのファイル（ページ番号は0から、終わりのページを含む）
+struct SplitPart {
+    name: String,
+    start: usize,
+    end: usize,
+}
+
+// 元のファイルと同じ場所への保存を防ぐ（上書きは常に別の操作として扱う）
+pub fn ensure_not_original(pdf_path: &str, output_path: &Path) -> Result<()> {
+    let original = std::fs::canonicalize(pdf_path)?;
+    let output = output_path
+        .parent()
+        .and_then(|parent| std::fs::canonicalize(parent).ok())
+        .and_then(|parent| output_path.file_name().map(|name| parent.join(name)))
+        .unwrap_or_else(|| output_path.to_path_buf());
+
+    if original == output {
+        anyhow::bail!("元のファイルには保存できません。別の名前を指定してください");
+    }
+    Ok(())
+}
+
+// 復号した内容を保護なしのファイルとして書き出さない
+pub fn ensure_not_password_protected(pdf_path: &str) -> Result<()> {
+    if has_document_password(pdf_path) {
+        anyhow::bail!("パスワードで保護された文書は保存できません");
+    }
+    Ok(())
+}
+
+// ページに元から設定されている回転に、ビューアーでの回転（時計回り）を加える
+pub fn combined_rotation(existing: PdfPageRenderRotation, rotation: RotationAngle) -> PdfPageRenderRotation {
+    let existing_quarters = match existing {
+        PdfPageRenderRotation::None => 0,
+        PdfPageRenderRotation::Degrees90 => 1,
+        PdfPageRenderRotation::Degrees180 => 2,
+        PdfPageRenderRotation::Degrees270 => 3,
+    };
+
+    match (existing_quarters + rotation as u8) % 4 {
+        1 => PdfPageRenderRotation::Degrees90,
+        2 => PdfPageRenderRotation::Degrees180,
+        3 => PdfPageRenderRotation::Degrees270,
+        _ => PdfPageRenderRotation::None,
+    }
+}
+
+// ページごとの回転を/Rotateに書き込んだコピーを保存（元のファイルは変更しない）
+// progressには(回転を書き込んだページ数, 回転するページ数)を渡す
+// PDFiumの所有スレッドには一定のページ数ごとに分けて依頼し、その間もページのレンダリングが進むようにする
+pub async fn save_rotated_copy(
+    pdf_path: &str,
+    rotations: &HashMap<usize, RotationAngle>,
+    output_path: &Path,
+    mut progress: impl FnMut(usize, usize),
+) -> Result<()> {
+    ensure_not_password_protected(pdf_path)?;
+    ensure_not_original(pdf_path, output_path)?;
+
+    let document = load_pdf_for_editing(pdf_path).await?;
+    let document_id = document.id();
+    let page_count = with_pdfium_async(move |service| Ok(service.editing_document(document_id)?.pages().len() as usize)).await?;
+
+    let mut rotated_pages: Vec<(usize, RotationAngle)> = rotations
+        .iter()
+        .filter(|&(&page_index, &rotation)| rotation != RotationAngle::None && page_index < page_count)
+        .map(|(&page_index, &rotation)| (page_index, rotation))
+        .collect();
+    rotated_pages.sort_by_key(|&(page_index, _)| page_index);
+
+    let total_pages = rotated_pages.len();
+    let mut rotated_count = 0;
+    progress(rotated_count, total_pages);
+
+    for chunk in rotated_pages.chunks(COPY_CHUNK_PAGES) {
+        let chunk = chunk.to_vec();
+        let chunk_len = chunk.len();
+        with_pdfium_async(move |service| {
+            service.modify_editing_document(document_id, |document, _| {
+                for (page_index, rotation) in chunk {
+                    let mut page = document.pages().get(page_index as u16)?;
+                    let existing = page.rotation().unwrap_or(PdfPageRenderRotation::None);
+                    page.set_rotation(combined_rotation(existing, rotation));
+                }
+                Ok(())
+            })
+        })
+        .await?;
+        rotated_count += chunk_len;
+        progress(rotated_count, total_pages);
+    }
+
+    save_editing_document(&document, output_path).await
+}
+
+// ページ整理の結果（並び順・回転・取り込み・空白ページ）を新しいファイルとして保存
+// progressには(書き出したページ数, 全体のページ数)を渡す
+// 結合・分割と同じく、PDFiumの所有スレッドには一定のページ数ごとに分けて依頼する
+pub async fn save_organized_pages(
+    pdf_path: &str,
+    pages: &[OrganizerPage],
+    output_path: &Path,
+    mut progress: impl FnMut(usize, usize),
+) -> Result<()> {
+    if pages.is_empty() {
+        anyhow::bail!("保存するページがありません");
+    }
+    if pages.len() > u16::MAX as usize {
+        anyhow::bail!("保存するページ数が多すぎます（{}ページ）", pages.len());
+    }
+
+    // ページを取り出す文書（開いている文書と、取り込んだ文書）
+    let mut source_paths = vec![pdf_path.to_string()];
+    for page in pages {
+        if let OrganizerPageSource::Imported { path, .. } = &page.source {
+            if !source_paths.contains(path) {
+                source_paths.push(path.clone());
+            }
+        }
+    }
+    for path in &source_paths {
+        ensure_not_password_protected(path)?;
+        ensure_not_original(path, output_path)?;
+    }
+
+    // 読み込んだ文書は保存が終わるまで所有スレッドに保持し、処理の中では番号で参照する
+    let mut sources = Vec::with_capacity(source_paths.len());
+    for path in &source_paths {
+        sources.push(load_pdf_for_editing(path).await?);
+    }
+    let source_ids: HashMap<String, u64> = source_paths.iter().cloned().zip(sources.iter().map(EditingDocument::id)).collect();
+
+    let output = create_pdf_for_editing().await?;
+    let output_id = output.id();
+    let total_pages = pages.len();
+    let mut written_pages = 0;
+    progress(written_pages, total_pages);
+
+    for chunk in pages.chunks(COPY_CHUNK_PAGES) {
+        let chunk = chunk.to_vec();
+        let chunk_len = chunk.len();
+        let chunk_start = written_pages;
+        let source_ids = source_ids.clone();
+        let pdf_path = pdf_path.to_string();
+        with_pdfium_async(move |service| {
+            service.modify_editing_document(output_id, |output, service| {
+                for (offset, page) in chunk.iter().enumerate() {
+                    let destination_index = (chunk_start + offset) as u16;
+                    match &page.source {
+                        OrganizerPageSource::Original(page_index) => {
+                            let source = service.editing_document(source_ids[&pdf_path])?;
+                            output.pages_mut().copy_page_from_document(source, *page_index as u16, destination_index)?;
+                        }
+                        OrganizerPageSource::Imported { path, page_index } => {
+                            let source = service.editing_document(source_ids[path])?;
+                            output.pages_mut().copy_page_from_document(source, *page_index as u16, destination_index)?;
+                        }
+                        OrganizerPageSource::Blank => {
+                            output.pages_mut().create_page_at_end(PdfPagePaperSize::Custom(PdfPoints::new(page.width), PdfPoints::new(page.height)))?;
+                        }
+                    }
+
+                    if page.rotation != RotationAngle::None {
+                        let mut output_page = output.pages().get(destination_index)?;
+                        let existing = output_page.rotation().unwrap_or(PdfPageRenderRotation::None);
+                        output_page.set_rotation(combined_rotation(existing, page.rotation));
+                    }
+                }
+                Ok(())
+            })
+        })
+        .await?;
+        written_pages += chunk_len;
+        progress(written_pages, total_pages);
+    }
+
+    save_editing_document(&output, output_path).await
+}
+
+// 複数のPDFを指定した順に結合して新しいファイルとして保存
+// progressには(コピー済みのページ数, 全体のページ数)を渡す
+// PDFiumの所有スレッドには一定のページ数ごとに分けて依頼し、その間もページのレンダリングが進むようにする
+pub async fn merge_pdfs(input_paths: &[String], output_path: &Path, mut progress: impl FnMut(usize, usize)) -> Result<()> {
+    if input_paths.len() < 2 {
+        anyhow::bail!("結合するPDFを2つ以上選択してください");
+    }
+    for path in input_paths {
+        ensure_not_password_protected(path)?;
+        ensure_not_original(path, output_path)?;
+    }
+
+    // 全体のページ数を先に数える（大きなファイルを全て同時に開いたままにしない）
+    let mut page_counts = Vec::with_capacity(input_paths.len());
+    for path in input_paths {
+        let path = path.clone();
+        let page_count = with_pdfium_async(move |service| Ok(service.load_for_editing(&path)?.pages().len() as usize)).await?;
+        page_counts.push(page_count);
+    }
+    let total_pages: usize = page_counts.iter().sum();
+    if total_pages > u16::MAX as usize {
+        anyhow::bail!("結合後のページ数が多すぎます（{}ページ）", total_pages);
+    }
+
+    let output = create_pdf_for_editing().await?;
+    let mut copied_pages = 0;
+    progress(copied_pages, total_pages);
+
+    for (path, &page_count) in input_paths.iter().zip(page_counts.iter()) {
+        let source = load_pdf_for_editing(path).await?;
+        for chunk_start in (0..page_count).step_by(COPY_CHUNK_PAGES) {
+            let chunk_end = (chunk_start + COPY_CHUNK_PAGES).min(page_count) - 1;
+            copy_page_range(&output, &source, chunk_start..=chunk_end, copied_pages).await?;
+            copied_pages += chunk_end - chunk_start + 1;
+            progress(copied_pages, total_pages);
+        }
+    }
+
+    save_editing_document(&output, output_path).await
+}
+
+// PDFを分割し、output_dirに「元の名前_区切り.pdf」として保存する
+// 既存のファイルは上書きせず、番号を付けた別の名前にする
+pub async fn split_pdf(pdf_path: &str, mode: &SplitMode, output_dir: &Path, mut progress: impl FnMut(usize, usize)) -> Result<Vec<PathBuf>> {
+    ensure_not_password_protected(pdf_path)?;
+
+    let source = load_pdf_for_editing(pdf_path).await?;
+    let source_id = source.id();
+    let needs_outline = *mode == SplitMode::TopLevelOutline;
+    let (page_count, bookmarks) = with_pdfium_async(move |service| {
+        let source = service.editing_document(source_id)?;
+        let bookmarks = if needs_outline { top_level_bookmarks(source) } else { Vec::new() };
+        Ok((source.pages().len() as usize, bookmarks))
+    })
+    .await?;
+    let parts = split_parts(page_count, mode, bookmarks)?;
+    if parts.is_empty() {
+        anyhow::bail!("分割するページがありません");
+    }
+
+    let stem = Path::new(pdf_path)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("document")
+        .to_string();
+    let total_pages: usize = parts.iter().map(|part| part.end - part.start + 1).sum();
+    let mut copied_pages = 0;
+    progress(copied_pages, total_pages);
+
+    let mut written = Vec::with_capacity(parts.len());
+    for part in &parts {
+        let output_path = unused_output_path(output_dir, &format!("{}_{}", stem, part.name));
+        ensure_not_original(pdf_path, &output_path)?;
+
+        let output = create_pdf_for_editing().await?;
+        for chunk_start in (part.start..=part.end).step_by(COPY_CHUNK_PAGES) {
+            let chunk_end = (chunk_start + COPY_CHUNK_PAGES - 1).min(part.end);
+            copy_page_range(&output, &source, chunk_start..=chunk_end, chunk_start - part.start).await?;
+            copied_pages += chunk_end - chunk_start + 1;
+            progress(copied_pages, total_pages);
+        }
+
+        save_editing_document(&output, &output_path).await?;
+        written.push(output_path);
+    }
+
+    Ok(written)
+}
+
+// 編集中の文書のページ範囲を、別の編集中の文書の指定位置にコピー
+async fn copy_page_range(output: &EditingDocument, source: &EditingDocument, pages: RangeInclusive<usize>, destination_index: usize) -> Result<()> {
+    let (output_id, source_id) = (output.id(), source.id());
+    with_pdfium_async(move |service| {
+        service.modify_editing_document(output_id, |output, service| {
+            let source = service.editing_document(source_id)?;
+            output.pages_mut().copy_page_range_from_document(
+                source,
+                *pages.start() as u16..=*pages.end() as u16,
+                destination_index as u16,
+            )?;
+            Ok(())
+        })
+    })
+    .await
+}
+
+async fn save_editing_document(document: &EditingDocument, output_path: &Path) -> Result<()> {
+    let id = document.id();
+    let output_path = output_path.to_path_buf();
+    with_pdfium_async(move |service| {
+        service.editing_document(id)?.save_to_file(&output_path)?;
+        Ok(())
+    })
+    .await
+}
+
+// 分割方法から、書き出すファイルごとのページ範囲を決める
+// bookmarksは最上位のしおりの(移動先のページ, 見出し)を文書内の順に並べたもの（しおりごとに分割する場合のみ使う）
+fn split_parts(page_count: usize, mode: &SplitMode, bookmarks: Vec<(usize, String)>) -> Result<Vec<SplitPart>> {
+    let parts = match mode {
+        SplitMode::PageRanges(spec) => parse_page_ranges(spec, page_count)?
+            .into_iter()
+            .map(|(start, end)| SplitPart { name: range_name(start, end), start, end })
+            .collect(),
+        SplitMode::EveryPages(pages_per_file) => {
+            if *pages_per_file == 0 {
+                anyhow::bail!("分割するページ数は1以上にしてください");
+            }
+            (0..page_count)
+                .step_by(*pages_per_file)
+                .map(|start| {
+                    let end = (start + pages_per_file - 1).min(page_count - 1);
+                    SplitPart { name: range_name(start, end), start, end }
+                })
+                .collect()
+        }
+        SplitMode::TopLevelOutline => outline_parts(bookmarks, page_count)?,
+    };
+
+    Ok(parts)
+}
+
+// ページ範囲を表すファイル名の一部（「p3」「p1-5」）
+fn range_name(start: usize, end: usize) -> String {
+    if start == end {
+        format!("p{}", start + 1)
+    } else {
+        format!("p{}-{}", start + 1, end + 1)
+    }
+}
+
+// 「1-3, 5, 8-」形式のページ範囲を0始まりの(開始, 終了)に変換
+fn parse_page_ranges(spec: &str, page_count: usize) -> Result<Vec<(usize, usize)>> {
+    if page_count == 0 {
+        anyhow::bail!("分割するページがありません");
+    }
+
+    let parse_page = |text: &str| -> Result<usize> {
+        let page: usize = text
+            .trim()
+            .parse()
+            .map_err(|_| anyhow::anyhow!("ページ番号を読み取れません: {}", text.trim()))?;
+        if page == 0 || page > page_count {
+            anyhow::bail!("ページ番号{}は範囲外です（1〜{}）", page, page_count);
+        }
+        Ok(page - 1)
+    };
+
+    let mut ranges = Vec::new();
+    for item in spec.split([',', '、']).map(str::trim).filter(|item| !item.is_empty()) {
+        let (start, end) = match item.split_once(['-', '〜', '~']) {
+            Some((start, end)) => {
+                let start = if start.trim().is_empty() { 0 } else { parse_page(start)? };
+                let end = if end.trim().is_empty() { page_count - 1 } else { parse_page(end)? };
+                (start, end)
+            }
+            None => {
+                let page = parse_page(item)?;
+                (page, page)
+            }
+        };
+        if start > end {
+            anyhow::bail!("ページ範囲の開始が終了より後になっています: {}", item);
+        }
+        ranges.push((start, end));
+    }
+
+    if ranges.is_empty() {
+        anyhow::bail!("ページ範囲を入力してください（例: 1-3, 5, 8-）");
+    }
+    Ok(ranges)
+}
+
+// 最上位のしおりの(移動先のページ, 見出し)を文書内の順に取得
+fn top_level_bookmarks(source: &PdfDocument) -> Vec<(usize, String)> {
+    let mut bookmarks = Vec::new();
+    let mut current = source.bookmarks().root();
+    while let Some(bookmark) = current {
+        if let Some(page_index) = bookmark_page_index(&bookmark) {
+            bookmarks.push((page_index, bookmark.title().unwrap_or_default()));
+        }
+        current = bookmark.next_sibling();
+    }
+    bookmarks
+}
+
+// 最上位のしおりの移動先ごとに区切る（最初のしおりより前のページは最初の区切りに含める）
+// 同じページを指すしおりは最初のものだけを使い、範囲外を指すしおりは無視する
+fn outline_parts(bookmarks: Vec<(usize, String)>, page_count: usize) -> Result<Vec<SplitPart>> {
+    let mut starts: Vec<(usize, String)> = Vec::new();
+    for (page_index, title) in bookmarks {
+        if page_index < page_count && !starts.iter().any(|(start, _)| *start == page_index) {
+            starts.push((page_index, title));
+        }
+    }
+
+    if starts.is_empty() {
+        anyhow::bail!("ページを指す最上位のしおりがありません");
+    }
+    starts.sort_by_key(|(start, _)| *start);
+    starts[0].0 = 0;
+
+    let parts = starts
+        .iter()
+        .enumerate()
+        .map(|(position, (start, title))| {
+            let end = starts.get(position + 1).map_or(page_count - 1, |(next_start, _)| next_start - 1);
+            let title = sanitize_file_name(title);
+            let name = if title.is_empty() {
+                format!("{:02}", position + 1)
+            } else {
+                format!("{:02}_{}", position + 1, title)
+            };
+            SplitPart { name, start: *start, end }
+        })
+        .collect();
+
+    Ok(parts)
+}
+
+// ファイル名に使えない文字を置き換え、長すぎる見出しは切り詰める（末尾の「.」と空白はWindowsで使えないため取り除く）
+fn sanitize_file_name(title: &str) -> String {
+    title
+        .trim()
+        .chars()
+        .map(|c| if c.is_control() || "\\/:*?\"<>|".contains(c) { '_' } else { c })
+        .take(MAX_PART_NAME_CHARS)
+        .collect::<String>()
+        .trim_start()
+        .trim_end_matches(|c: char| c == '.' || c.is_whitespace())
+        .to_string()
+}
+
+// 既存のファイルと重ならない保存先（「名前 (2).pdf」のように番号を付ける）
+fn unused_output_path(output_dir: &Path, base_name: &str) -> PathBuf {
+    let mut path = output_dir.join(format!("{}.pdf", base_name));
+    let mut number = 2;
+    while path.exists() {
+        path = output_dir.join(format!("{} ({}).pdf", base_name, number));
+        number += 1;
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_pages_and_ranges() {
+        assert_eq!(parse_page_ranges("1-3, 5, 8-", 10).unwrap(), vec![(0, 2), (4, 4), (7, 9)]);
+        assert_eq!(parse_page_ranges("-2", 10).unwrap(), vec![(0, 1)]);
+        assert_eq!(parse_page_ranges("10", 10).unwrap(), vec![(9, 9)]);
+    }
+
+    #[test]
+    fn ignores_whitespace_and_empty_parts() {
+        assert_eq!(parse_page_ranges("  2 - 4 ,6 ", 10).unwrap(), vec![(1, 3), (5, 5)]);
+        assert_eq!(parse_page_ranges("1,,3,", 10).unwrap(), vec![(0, 0), (2, 2)]);
+        assert_eq!(parse_page_ranges("1〜2、4", 10).unwrap(), vec![(0, 1), (3, 3)]);
+    }
+
+    #[test]
+    fn keeps_overlapping_ranges_as_separate_parts() {
+        assert_eq!(parse_page_ranges("1-5, 3-7", 10).unwrap(), vec![(0, 4), (2, 6)]);
+    }
+
+    #[test]
+    fn rejects_reversed_ranges() {
+        assert!(parse_page_ranges("5-2", 10).is_err());
+    }
+
+    #[test]
+    fn rejects_out_of_range_pages() {
+        assert!(parse_page_ranges("0", 10).is_err());
+        assert!(parse_page_ranges("11", 10).is_err());
+        assert!(parse_page_ranges("3-11", 10).is_err());
+        assert!(parse_page_ranges("0-", 10).is_err());
+        assert!(parse_page_ranges("1-", 0).is_err());
+    }
+
+    #[test]
+    fn rejects_empty_or_invalid_input() {
+        assert!(parse_page_ranges("", 10).is_err());
+        assert!(parse_page_ranges(" , ", 10).is_err());
+        assert!(parse_page_ranges("a-3", 10).is_err());
+        assert!(parse_page_ranges("1-2-3", 10).is_err());
+    }
+
+    fn part_list(parts: Vec<SplitPart>) -> Vec<(String, usize, usize)> {
+        parts.into_iter().map(|part| (part.name, part.start, part.end)).collect()
+    }
+
+    fn bookmarks(entries: &[(usize, &str)]) -> Vec<(usize, String)> {
+        entries.iter().map(|(page_index, title)| (*page_index, title.to_string())).collect()
+    }
+
+    #[test]
+    fn splits_every_pages_on_exact_boundaries() {
+        let parts = split_parts(10, &SplitMode::EveryPages(5), Vec::new()).unwrap();
+        assert_eq!(part_list(parts), vec![("p1-5".to_string(), 0, 4), ("p6-10".to_string(), 5, 9)]);
+
+        let parts = split_parts(3, &SplitMode::EveryPages(1), Vec::new()).unwrap();
+        assert_eq!(part_list(parts), vec![("p1".to_string(), 0, 0), ("p2".to_string(), 1, 1), ("p3".to_string(), 2, 2)]);
+    }
+
+    #[test]
+    fn keeps_last_partial_chunk() {
+        let parts = split_parts(11, &SplitMode::EveryPages(5), Vec::new()).unwrap();
+        assert_eq!(
+            part_list(parts),
+            vec![("p1-5".to_string(), 0, 4), ("p6-10".to_string(), 5, 9), ("p11".to_string(), 10, 10)]
+        );
+
+        let parts = split_parts(4, &SplitMode::EveryPages(10), Vec::new()).unwrap();
+        assert_eq!(part_list(parts), vec![("p1-4".to_string(), 0, 3)]);
+    }
+
+    #[test]
+    fn rejects_zero_pages_per_file() {
+        assert!(split_parts(10, &SplitMode::EveryPages(0), Vec::new()).is_err());
+    }
+
+    #[test]
+    fn names_page_range_parts() {
+        let parts = split_parts(10, &SplitMode::PageRanges("1-3, 5".to_string()), Vec::new()).unwrap();
+        assert_eq!(part_list(parts), vec![("p1-3".to_string(), 0, 2), ("p5".to_string(), 4, 4)]);
+    }
+
+    #[test]
+    fn splits_at_top_level_bookmarks() {
+        let parts = split_parts(10, &SplitMode::TopLevelOutline, bookmarks(&[(0, "序章"), (4, "本編"), (8, "付録")])).unwrap();
+        assert_eq!(
+            part_list(parts),
+            vec![("01_序章".to_string(), 0, 3), ("02_本編".to_string(), 4, 7), ("03_付録".to_string(), 8, 9)]
+        );
+    }
+
+    #[test]
+    fn sorts_bookmarks_out_of_order() {
+        let parts = outline_parts(bookmarks(&[(6, "C"), (0, "A"), (3, "B")]), 10).unwrap();
+        assert_eq!(
+            part_list(parts),
+            vec![("01_A".to_string(), 0, 2), ("02_B".to_string(), 3, 5), ("03_C".to_string(), 6, 9)]
+        );
+    }
+
+    #[test]
+    fn keeps_first_bookmark_on_the_same_page() {
+        let parts = outline_parts(bookmarks(&[(0, "表紙"), (5, "第1章"), (5, "第1節"), (0, "目次")]), 8).unwrap();
+        assert_eq!(part_list(parts), vec![("01_表紙".to_string(), 0, 4), ("02_第1章".to_string(), 5, 7)]);
+    }
+
+    #[test]
+    fn includes_leading_pages_in_first_outline_part() {
+        let parts = outline_parts(bookmarks(&[(2, "はじめに"), (6, "")]), 10).unwrap();
+        assert_eq!(part_list(parts), vec![("01_はじめに".to_string(), 0, 5), ("02".to_string(), 6, 9)]);
+    }
+
+    #[test]
+    fn ignores_bookmarks_outside_the_document() {
+        let parts = outline_parts(bookmarks(&[(0, "A"), (12, "範囲外")]), 10).unwrap();
+        assert_eq!(part_list(parts), vec![("01_A".to_string(), 0, 9)]);
+
+        assert!(outline_parts(bookmarks(&[(10, "範囲外")]), 10).is_err());
+        assert!(outline_parts(Vec::new(), 10).is_err());
+    }
+
+    #[test]
+    fn sanitizes_part_names() {
+        assert_eq!(sanitize_file_name("A/B\\C:D*E?F\"G<H>I|J"), "A_B_C_D_E_F_G_H_I_J");
+        assert_eq!(sanitize_file_name("改行\nと\tタブ"), "改行_と_タブ");
+        assert_eq!(sanitize_file_name("  第1章 概要 ..  "), "第1章 概要");
+        assert_eq!(sanitize_file_name("..."), "");
+    }
+
+    #[test]
+    fn truncates_long_part_names() {
+        let title = "章".repeat(MAX_PART_NAME_CHARS + 10);
+        assert_eq!(sanitize_file_name(&title), "章".repeat(MAX_PART_NAME_CHARS));
+
+        // 切り詰めた結果の末尾の空白や「.」も取り除く
+        let title = format!("{} . tail", "a".repeat(MAX_PART_NAME_CHARS - 3));
+        assert_eq!(sanitize_file_name(&title), "a".repeat(MAX_PART_NAME_CHARS - 3));
+    }
+
+    #[test]
+    fn numbers_output_paths_that_already_exist() {
+        let output_dir = std::env::temp_dir().join(format!("pdf_split_test_{}", std::process::id()));
+        std::fs::create_dir_all(&output_dir).unwrap();
+
+        let first = unused_output_path(&output_dir, "p1-5");
+        assert_eq!(first, output_dir.join("p1-5.pdf"));
+        std::fs::write(&first, b"").unwrap();
+
+        let second = unused_output_path(&output_dir, "p1-5");
+        assert_eq!(second, output_dir.join("p1-5 (2).pdf"));
+        std::fs::write(&second, b"").unwrap();
+
+        assert_eq!(unused_output_path(&output_dir, "p1-5"), output_dir.join("p1-5 (3).pdf"));
+        assert_eq!(unused_output_path(&output_dir, "p6-10"), output_dir.join("p6-10.pdf"));
+
+        let _ = std::fs::remove_dir_all(&output_dir);
+    }
+}
@@ -0,0 +1,115 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use dioxus_desktop::wry::http::{header, Request, Response, StatusCode};
+
+// レンダリング済み画像をWebViewに配信するカスタムURIスキーム名
+pub const PAGE_IMAGE_SCHEME: &str = "pdfimage";
+
+// 画像ID -> エンコード済みの画像データ
+static IMAGE_STORE: OnceLock<Mutex<HashMap<u64, Arc<Vec<u8>>>>> = OnceLock::new();
+static NEXT_IMAGE_ID: AtomicU64 = AtomicU64::new(1);
+
+fn image_store() -> &'static Mutex<HashMap<u64, Arc<Vec<u8>>>> {
+    IMAGE_STORE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+struct StoredImage {
+    id: u64,
+    byte_len: usize,
+}
+
+impl Drop for StoredImage {
+    fn drop(&mut self) {
+        if let Ok(mut store) = image_store().lock() {
+            store.remove(&self.id);
+        }
+    }
+}
+
+// 画像ストアに登録されたページ画像への参照
+// 要素からは短いURLだけを参照し、全ての複製が破棄されるとストアからも削除される
+#[derive(Clone)]
+pub struct PageImage {
+    image: Arc<StoredImage>,
+}
+
+impl PageImage {
+    pub fn new(png_data: Vec<u8>) -> Self {
+        let id = NEXT_IMAGE_ID.fetch_add(1, Ordering::Relaxed);
+        let byte_len = png_data.len();
+        if let Ok(mut store) = image_store().lock() {
+            store.insert(id, Arc::new(png_data));
+        }
+
+        Self {
+            image: Arc::new(StoredImage { id, byte_len }),
+        }
+    }
+
+    pub fn byte_len(&self) -> usize {
+        self.image.byte_len
+    }
+
+    // エンコード済みの画像データ（ディスクキャッシュへの保存用）
+    pub fn bytes(&self) -> Option<Arc<Vec<u8>>> {
+        image_store().lock().ok()?.get(&self.image.id).cloned()
+    }
+
+    // WebViewから参照するURL（WindowsのWebView2ではカスタムスキームをhttp://<scheme>.localhostとして扱う）
+    pub fn url(&self) -> String {
+        if cfg!(windows) {
+            format!("http://{}.localhost/{}.png", PAGE_IMAGE_SCHEME, self.image.id)
+        } else {
+            format!("{}://localhost/{}.png", PAGE_IMAGE_SCHEME, self.image.id)
+        }
+    }
+}
+
+impl PartialEq for PageImage {
+    fn eq(&self, other: &Self) -> bool {
+        self.image.id == other.image.id
+    }
+}
+
+impl fmt::Debug for PageImage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PageImage")
+            .field("id", &self.image.id)
+            .field("byte_len", &self.image.byte_len)
+            .finish()
+    }
+}
+
+impl fmt::Display for PageImage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.url())
+    }
+}
+
+// カスタムスキームへのリクエストに対して画像ストアのデータを返す
+pub fn handle_page_image_request(request: Request<Vec<u8>>) -> Response<Cow<'static, [u8]>> {
+    let image_data = request
+        .uri()
+        .path()
+        .trim_start_matches('/')
+        .strip_suffix(".png")
+        .and_then(|id| id.parse::<u64>().ok())
+        .and_then(|id| image_store().lock().ok()?.get(&id).cloned());
+
+    let response = match image_data {
+        Some(image_data) => Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, "image/png")
+            // IDは使い回さないため、同じURLの内容が変わることはない
+            .header(header::CACHE_CONTROL, "max-age=31536000, immutable")
+            .body(Cow::Owned(image_data.as_ref().clone())),
+        None => Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Cow::Borrowed(&[][..])),
+    };
+
+    response.unwrap_or_else(|_| Response::new(Cow::Borrowed(&[][..])))
+}
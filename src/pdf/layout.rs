@@ -0,0 +1,388 @@
+use crate::types::{TextBounds, TextElement};
+
+// 単語の間隔がこの値（文字サイズに対する比率）を超えると、同じ行でも別の行（段）として扱う
+const MAX_WORD_GAP_RATIO: f32 = 1.0;
+// 行の間隔がこの値（行の高さに対する比率）以下なら同じ段落とみなす
+const MAX_LINE_GAP_RATIO: f32 = 0.8;
+// 段落の文字サイズの差がこの比率以内なら同じ段落とみなす
+const MAX_FONT_SIZE_RATIO: f32 = 1.3;
+// 段組み・段落の区切りとみなす最小の余白（ポイント）
+const MIN_CUT_GAP: f32 = 4.0;
+
+// 左上原点の矩形の範囲
+#[derive(Clone, Copy)]
+struct Extent {
+    left: f32,
+    top: f32,
+    right: f32,
+    bottom: f32,
+}
+
+impl Extent {
+    fn of(bounds: &TextBounds) -> Self {
+        Self {
+            left: bounds.x,
+            top: bounds.y,
+            right: bounds.x + bounds.width,
+            bottom: bounds.y + bounds.height,
+        }
+    }
+
+    fn union(self, other: Extent) -> Self {
+        Self {
+            left: self.left.min(other.left),
+            top: self.top.min(other.top),
+            right: self.right.max(other.right),
+            bottom: self.bottom.max(other.bottom),
+        }
+    }
+
+    fn height(&self) -> f32 {
+        self.bottom - self.top
+    }
+
+    fn center_y(&self) -> f32 {
+        (self.top + self.bottom) / 2.0
+    }
+}
+
+// 左から順に並んだ単語の行
+struct TextLine {
+    words: Vec<TextElement>,
+    extent: Extent,
+    font_size: f32,
+}
+
+// 行を上から順に並べた段落
+struct TextBlock {
+    lines: Vec<TextLine>,
+    extent: Extent,
+    font_size: f32,
+}
+
+// 単語を行・段落・段組みにまとめ、読む順番に並べ替える
+// 同じ位置に重ねて描画された単語（疑似太字など）は1つにまとめる
+pub fn arrange_in_reading_order(words: Vec<TextElement>) -> Vec<TextElement> {
+    // 縦書きが主のページは座標を転置し、横書きと同じ手順で解析する
+    // （列が上から下への行に、右から左への列の並びが上から下への行の並びになる）
+    let vertical_chars: usize = words.iter().filter(|word| word.vertical).map(|word| word.text.chars().count()).sum();
+    let total_chars: usize = words.iter().map(|word| word.text.chars().count()).sum();
+    let is_vertical_page = vertical_chars * 2 > total_chars;
+    let words = if is_vertical_page { words.into_iter().map(transpose).collect() } else { words };
+
+    let lines = build_lines(words);
+    let blocks = build_blocks(lines);
+
+    let mut ordered = Vec::with_capacity(blocks.len());
+    xy_cut(blocks, &mut ordered);
+
+    let words = ordered
+        .into_iter()
+        .flat_map(|block| block.lines)
+        .flat_map(|line| line.words);
+    if is_vertical_page {
+        words.map(transpose_back).collect()
+    } else {
+        words.collect()
+    }
+}
+
+// 縦書きの座標を横書きの座標に変換（下方向をx軸に、左方向をy軸にする）
+fn transpose(mut word: TextElement) -> TextElement {
+    let bounds = &word.bounds;
+    word.bounds = TextBounds {
+        x: bounds.y,
+        y: -(bounds.x + bounds.width),
+        width: bounds.height,
+        height: bounds.width,
+    };
+    word
+}
+
+fn transpose_back(mut word: TextElement) -> TextElement {
+    let bounds = &word.bounds;
+    word.bounds = TextBounds {
+        x: -(bounds.y + bounds.height),
+        y: bounds.x,
+        width: bounds.height,
+        height: bounds.width,
+    };
+    word
+}
+
+// 縦方向に重なる単語を同じ行の候補にまとめ、大きな空白で区切って行にする
+fn build_lines(mut words: Vec<TextElement>) -> Vec<TextLine> {
+    words.retain(|word| !word.text.trim().is_empty() && word.bounds.height > 0.0);
+    words.sort_by(|a, b| Extent::of(&a.bounds).center_y().total_cmp(&Extent::of(&b.bounds).center_y()));
+
+    // 中心の高さが行の範囲に入る単語を同じ行の候補とする
+    let mut rows: Vec<(Extent, Vec<TextElement>)> = Vec::new();
+    for word in words {
+        let extent = Extent::of(&word.bounds);
+        match rows.last_mut() {
+            Some((row_extent, row_words)) if extent.center_y() <= row_extent.bottom => {
+                *row_extent = row_extent.union(extent);
+                row_words.push(word);
+            }
+            _ => rows.push((extent, vec![word])),
+        }
+    }
+
+    let mut lines = Vec::new();
+    for (_, mut row_words) in rows {
+        row_words.sort_by(|a, b| a.bounds.x.total_cmp(&b.bounds.x));
+
+        let mut current: Option<TextLine> = None;
+        for word in row_words {
+            let extent = Extent::of(&word.bounds);
+            if let Some(line) = current.as_mut() {
+                let gap = extent.left - line.extent.right;
+                if is_duplicate(line, &word) {
+                    continue;
+                }
+                if gap <= line.font_size.max(word.font_size) * MAX_WORD_GAP_RATIO {
+                    line.extent = line.extent.union(extent);
+                    line.font_size = line.font_size.max(word.font_size);
+                    line.words.push(word);
+                    continue;
+                }
+            }
+
+            if let Some(line) = current.take() {
+                lines.push(line);
+            }
+            current = Some(TextLine {
+                extent,
+                font_size: word.font_size,
+                words: vec![word],
+            });
+        }
+        if let Some(line) = current.take() {
+            lines.push(line);
+        }
+    }
+
+    lines
+}
+
+// 直前の単語と同じ文字列がほぼ同じ位置に描画されている場合
+fn is_duplicate(line: &TextLine, word: &TextElement) -> bool {
+    line.words.last().map_or(false, |last| {
+        let tolerance = last.bounds.height.max(word.bounds.height) * 0.5;
+        last.text == word.text
+            && (last.bounds.x - word.bounds.x).abs() <= tolerance
+            && (last.bounds.y - word.bounds.y).abs() <= tolerance
+    })
+}
+
+// 上から順に、直前の行と近く横方向に重なる行を同じ段落にまとめる
+fn build_blocks(mut lines: Vec<TextLine>) -> Vec<TextBlock> {
+    lines.sort_by(|a, b| a.extent.top.total_cmp(&b.extent.top).then(a.extent.left.total_cmp(&b.extent.left)));
+
+    let mut blocks: Vec<TextBlock> = Vec::new();
+    for line in lines {
+        let target = blocks.iter().rposition(|block| {
+            let Some(last_line) = block.lines.last() else { return false };
+            let gap = line.extent.top - last_line.extent.bottom;
+            let overlaps = line.extent.left < block.extent.right && block.extent.left < line.extent.right;
+            let font_ratio = line.font_size.max(block.font_size) / line.font_size.min(block.font_size).max(0.1);
+            overlaps
+                && gap >= -line.extent.height() * 0.5
+                && gap <= line.extent.height().max(last_line.extent.height()) * MAX_LINE_GAP_RATIO
+                && font_ratio <= MAX_FONT_SIZE_RATIO
+        });
+
+        match target {
+            Some(index) => {
+                let block = &mut blocks[index];
+                block.extent = block.extent.union(line.extent);
+                block.lines.push(line);
+            }
+            None => blocks.push(TextBlock {
+                extent: line.extent,
+                font_size: line.font_size,
+                lines: vec![line],
+            }),
+        }
+    }
+
+    blocks
+}
+
+// 再帰的なXY-cut: 縦の余白（段組み）で左から右へ、次に横の余白で上から下へ分割する
+fn xy_cut(mut blocks: Vec<TextBlock>, ordered: &mut Vec<TextBlock>) {
+    if blocks.len() <= 1 {
+        ordered.extend(blocks);
+        return;
+    }
+
+    blocks.sort_by(|a, b| a.extent.left.total_cmp(&b.extent.left));
+    let columns = split_at_gaps(blocks, |extent| (extent.left, extent.right));
+    if columns.len() > 1 {
+        for column in columns {
+            xy_cut(column, ordered);
+        }
+        return;
+    }
+    let mut blocks = columns.into_iter().flatten().collect::<Vec<_>>();
+
+    blocks.sort_by(|a, b| a.extent.top.total_cmp(&b.extent.top));
+    let rows = split_at_gaps(blocks, |extent| (extent.top, extent.bottom));
+    if rows.len() > 1 {
+        for row in rows {
+            xy_cut(row, ordered);
+        }
+        return;
+    }
+
+    // これ以上分割できない場合は上から、同じ高さなら左から
+    let mut blocks = rows.into_iter().flatten().collect::<Vec<_>>();
+    blocks.sort_by(|a, b| a.extent.top.total_cmp(&b.extent.top).then(a.extent.left.total_cmp(&b.extent.left)));
+    ordered.extend(blocks);
+}
+
+// 開始位置の順に並んだ段落を、どの段落も跨がない余白の位置で分割
+fn split_at_gaps(blocks: Vec<TextBlock>, range: impl Fn(&Extent) -> (f32, f32)) -> Vec<Vec<TextBlock>> {
+    let mut groups: Vec<Vec<TextBlock>> = Vec::new();
+    let mut group_end = f32::NEG_INFINITY;
+
+    for block in blocks {
+        let (start, end) = range(&block.extent);
+        match groups.last_mut() {
+            Some(group) if start - group_end < MIN_CUT_GAP => group.push(block),
+            _ => groups.push(vec![block]),
+        }
+        group_end = group_end.max(end);
+    }
+
+    groups
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(text: &str, x: f32, y: f32, width: f32, height: f32) -> TextElement {
+        TextElement {
+            text: text.to_string(),
+            bounds: TextBounds { x, y, width, height },
+            font_size: height,
+            vertical: false,
+        }
+    }
+
+    fn texts(words: &[TextElement]) -> Vec<&str> {
+        words.iter().map(|word| word.text.as_str()).collect()
+    }
+
+    // 左右2段の本文（各段3行、1行に2単語）。行の高さは左右で揃っている
+    fn two_columns() -> Vec<TextElement> {
+        let mut words = Vec::new();
+        for row in 0..3 {
+            let y = 100.0 + row as f32 * 15.0;
+            words.push(word(&format!("R{}a", row + 1), 320.0, y, 40.0, 10.0));
+            words.push(word(&format!("R{}b", row + 1), 365.0, y, 40.0, 10.0));
+            words.push(word(&format!("L{}a", row + 1), 50.0, y, 40.0, 10.0));
+            words.push(word(&format!("L{}b", row + 1), 95.0, y, 40.0, 10.0));
+        }
+        words
+    }
+
+    #[test]
+    fn reads_left_column_before_right_column() {
+        let ordered = arrange_in_reading_order(two_columns());
+        assert_eq!(
+            texts(&ordered),
+            ["L1a", "L1b", "L2a", "L2b", "L3a", "L3b", "R1a", "R1b", "R2a", "R2b", "R3a", "R3b"]
+        );
+    }
+
+    #[test]
+    fn reads_full_width_heading_before_columns() {
+        let mut words = two_columns();
+        words.push(word("Heading", 50.0, 50.0, 355.0, 20.0));
+        let ordered = arrange_in_reading_order(words);
+        assert_eq!(
+            texts(&ordered),
+            ["Heading", "L1a", "L1b", "L2a", "L2b", "L3a", "L3b", "R1a", "R1b", "R2a", "R2b", "R3a", "R3b"]
+        );
+    }
+
+    #[test]
+    fn drops_glyphs_drawn_twice_at_the_same_position() {
+        // 疑似太字のように、少しずらして同じ単語を重ねて描画したもの
+        let words = vec![
+            word("Bold", 50.0, 100.0, 40.0, 10.0),
+            word("Bold", 50.3, 100.2, 40.0, 10.0),
+            word("text", 95.0, 100.0, 40.0, 10.0),
+        ];
+        assert_eq!(texts(&arrange_in_reading_order(words)), ["Bold", "text"]);
+    }
+
+    #[test]
+    fn keeps_repeated_words_and_different_overlapping_words() {
+        // 同じ単語でも離れていれば別の単語、重なっていても文字列が違えば別の単語
+        let words = vec![
+            word("the", 50.0, 100.0, 20.0, 10.0),
+            word("the", 75.0, 100.0, 20.0, 10.0),
+            word("x", 75.5, 100.0, 5.0, 10.0),
+        ];
+        assert_eq!(texts(&arrange_in_reading_order(words)), ["the", "the", "x"]);
+    }
+
+    #[test]
+    fn is_duplicate_uses_half_the_glyph_height_as_tolerance() {
+        let line = TextLine {
+            words: vec![word("A", 10.0, 10.0, 8.0, 10.0)],
+            extent: Extent::of(&TextBounds { x: 10.0, y: 10.0, width: 8.0, height: 10.0 }),
+            font_size: 10.0,
+        };
+        assert!(is_duplicate(&line, &word("A", 14.9, 10.0, 8.0, 10.0)));
+        assert!(!is_duplicate(&line, &word("A", 15.1, 10.0, 8.0, 10.0)));
+        assert!(!is_duplicate(&line, &word("B", 10.0, 10.0, 8.0, 10.0)));
+    }
+
+    #[test]
+    fn skips_blank_words() {
+        let words = vec![word(" ", 50.0, 100.0, 5.0, 10.0), word("a", 60.0, 100.0, 5.0, 10.0), word("b", 70.0, 100.0, 5.0, 0.0)];
+        assert_eq!(texts(&arrange_in_reading_order(words)), ["a"]);
+    }
+
+    fn vertical_word(text: &str, x: f32, y: f32) -> TextElement {
+        let height = text.chars().count() as f32 * 10.0;
+        TextElement { vertical: true, ..word(text, x, y, 10.0, height) }
+    }
+
+    #[test]
+    fn transpose_back_restores_bounds() {
+        let original = vertical_word("縦書き", 480.0, 120.0);
+        assert_eq!(transpose_back(transpose(original.clone())), original);
+    }
+
+    #[test]
+    fn reads_vertical_columns_from_right_to_left() {
+        // 右から3列、各列は上から2単語
+        let mut words = Vec::new();
+        for (column, x) in [470.0, 485.0, 500.0].into_iter().enumerate() {
+            let column = 3 - column;
+            words.push(vertical_word(&format!("{}上", column), x, 100.0));
+            words.push(vertical_word(&format!("{}下", column), x, 120.0));
+        }
+
+        let ordered = arrange_in_reading_order(words);
+        assert_eq!(texts(&ordered), ["1上", "1下", "2上", "2下", "3上", "3下"]);
+
+        // 座標は元の縦書きの位置に戻っている
+        assert_eq!(ordered[0].bounds, TextBounds { x: 500.0, y: 100.0, width: 10.0, height: 20.0 });
+        assert!(ordered.iter().all(|word| word.vertical));
+    }
+
+    #[test]
+    fn mostly_horizontal_page_keeps_horizontal_order() {
+        let mut words = two_columns();
+        words.push(vertical_word("縦", 580.0, 100.0));
+        let ordered = arrange_in_reading_order(words);
+        assert_eq!(ordered.first().map(|word| word.text.as_str()), Some("L1a"));
+    }
+}
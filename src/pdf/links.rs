@@ -0,0 +1,77 @@
+use anyhow::Result;
+use pdfium_render::prelude::*;
+use crate::types::{LinkTarget, PdfLink, TextBounds};
+
+// ページ内のリンク注釈を抽出（左上原点のページ座標）
+pub fn extract_links(document: &PdfDocument, page: &PdfPage, page_height: f32) -> Vec<PdfLink> {
+    let mut links = Vec::new();
+
+    for link in page.links().iter() {
+        let Ok(rect) = link.rect() else { continue };
+        if rect.width().value <= 0.0 || rect.height().value <= 0.0 {
+            continue;
+        }
+
+        let target = match link.action() {
+            Some(PdfAction::Uri(action)) => action.uri().ok().map(LinkTarget::Uri),
+            Some(PdfAction::LocalDestination(action)) => action.destination().ok().and_then(|destination| destination_target(document, &destination)),
+            _ => link.destination().and_then(|destination| destination_target(document, &destination)),
+        };
+        let Some(target) = target else { continue };
+
+        links.push(PdfLink {
+            bounds: TextBounds {
+                x: rect.left().value,
+                y: page_height - rect.top().value,
+                width: rect.width().value,
+                height: rect.height().value,
+            },
+            target,
+        });
+    }
+
+    links
+}
+
+// 文書内の移動先（ページと、指定されていればページ上端からの位置）
+fn destination_target(document: &PdfDocument, destination: &PdfDestination) -> Option<LinkTarget> {
+    let page_index = destination.page_index().ok()? as usize;
+
+    let (x, y) = match destination.view_settings() {
+        Ok(PdfDestinationViewSettings::SpecificCoordinatesAndZoom(x, y, _)) => {
+            let page_height = document.pages().page_size(page_index as u16).ok().map(|size| size.height().value);
+            (
+                x.map(|x| x.value.max(0.0)),
+                y.zip(page_height).map(|(y, page_height)| (page_height - y.value).max(0.0)),
+            )
+        }
+        _ => (None, None),
+    };
+
+    Some(LinkTarget::Page { page_index, y, x })
+}
+
+// 外部リンクをシステムの既定のブラウザ等で開く（安全のため一部のスキームのみ許可）
+pub fn open_external_link(uri: &str) -> Result<()> {
+    let lower = uri.trim().to_ascii_lowercase();
+    if !(lower.starts_with("http://") || lower.starts_with("https://") || lower.starts_with("mailto:")) {
+        return Err(anyhow::anyhow!("対応していないリンクの形式です: {}", uri));
+    }
+
+    let mut command = if cfg!(target_os = "macos") {
+        let mut command = std::process::Command::new("open");
+        command.arg(uri);
+        command
+    } else if cfg!(target_os = "windows") {
+        let mut command = std::process::Command::new("cmd");
+        command.args(["/C", "start", "", uri]);
+        command
+    } else {
+        let mut command = std::process::Command::new("xdg-open");
+        command.arg(uri);
+        command
+    };
+
+    command.spawn()?;
+    Ok(())
+}
@@ -1,7 +1,23 @@
+pub mod cache;
+pub mod document;
+pub mod editing;
+pub mod image_store;
+pub mod layout;
+pub mod links;
 pub mod renderer;
+pub mod search;
 pub mod text;
 pub mod utils;
+pub mod worker;
 
+pub use cache::*;
+pub use document::*;
+pub use editing::*;
+pub use image_store::*;
+pub use layout::*;
+pub use links::*;
 pub use renderer::*;
+pub use search::*;
 pub use text::*;
-pub use utils::*;
\ No newline at end of file
+pub use utils::*;
+pub use worker::*;
\ No newline at end of file
@@ -1,104 +1,485 @@
 use anyhow::Result;
 use pdfium_render::prelude::*;
-use base64::Engine;
-use crate::types::{PdfPageData, TextElement, TextBounds, RotationAngle};
-use crate::pdf::{get_pdfium_library_path, filter_overlapping_text};
+use crate::types::{PdfLink, PdfPageData, PdfTileData, RotationAngle, TextElement, TileKey, TileRegion};
+use crate::pdf::{with_pdf_document, extract_text_elements, arrange_in_reading_order, extract_links, PageImage};
 
-pub fn render_pdf_page_with_text(pdf_path: &str, page_index: usize, rotation: RotationAngle) -> Result<PdfPageData> {
-    let library_path = get_pdfium_library_path()?;
-    let pdfium = Pdfium::new(
-        Pdfium::bind_to_library(library_path)?
-    );
+// 1辺あたりの最大ピクセル数（これを超える場合は倍率を下げてレンダリング）
+const MAX_RENDER_DIMENSION: i32 = 8192;
+
+// ページ全体の画素数がこれを超える場合はタイル単位でレンダリングする
+const TILED_RENDER_THRESHOLD: f32 = 3000.0 * 3000.0;
+
+// タイル表示するページの下敷きとして使うプレビュー画像の最大画素数
+const TILED_PREVIEW_MAX_PIXELS: f32 = 1500.0 * 1500.0;
+
+// タイル1枚の1辺のピクセル数
+pub const TILE_SIZE: u32 = 512;
+
+// サムネイル画像の幅（ピクセル、高解像度ディスプレイ向けに表示幅より大きめ）
+pub const THUMBNAIL_WIDTH: f32 = 200.0;
+
+// リンク先プレビューの幅と最大の高さ（ピクセル）
+pub const LINK_PREVIEW_WIDTH: u32 = 480;
+pub const LINK_PREVIEW_MAX_HEIGHT: u32 = 280;
+
+// 画面外のタイルを保持しておく上限（表示中のタイルはこれを超えても破棄しない）
+pub const MAX_CACHED_TILES: usize = 64;
+
+// ページ全体を1枚のビットマップにするには大きすぎるかどうか（寸法は回転後のポイント単位）
+pub fn needs_tiled_rendering(page_width: f32, page_height: f32, scale: f32) -> bool {
+    let pixel_width = page_width * scale;
+    let pixel_height = page_height * scale;
+    pixel_width * pixel_height > TILED_RENDER_THRESHOLD
+        || pixel_width > MAX_RENDER_DIMENSION as f32
+        || pixel_height > MAX_RENDER_DIMENSION as f32
+}
+
+// ページ全体をレンダリングした場合のピクセル寸法
+pub fn full_page_pixel_size(page_width: f32, page_height: f32, scale: f32) -> (u32, u32) {
+    (
+        (page_width * scale).round().max(1.0) as u32,
+        (page_height * scale).round().max(1.0) as u32,
+    )
+}
+
+// 表示されている範囲（ページに対する0.0-1.0の割合）にかかるタイルの一覧
+pub fn visible_tile_regions(page_width: f32, page_height: f32, scale: f32, visible: (f32, f32, f32, f32)) -> Vec<TileRegion> {
+    let (full_width, full_height) = full_page_pixel_size(page_width, page_height, scale);
+    let (left, top, right, bottom) = visible;
+
+    let column_start = (left.clamp(0.0, 1.0) * full_width as f32) as u32 / TILE_SIZE;
+    let column_end = ((right.clamp(0.0, 1.0) * full_width as f32).ceil() as u32).div_ceil(TILE_SIZE);
+    let row_start = (top.clamp(0.0, 1.0) * full_height as f32) as u32 / TILE_SIZE;
+    let row_end = ((bottom.clamp(0.0, 1.0) * full_height as f32).ceil() as u32).div_ceil(TILE_SIZE);
+
+    let mut regions = Vec::new();
+    for row in row_start..row_end {
+        for column in column_start..column_end {
+            let x = column * TILE_SIZE;
+            let y = row * TILE_SIZE;
+            if x >= full_width || y >= full_height {
+                continue;
+            }
+            regions.push(TileRegion {
+                x,
+                y,
+                width: TILE_SIZE.min(full_width - x),
+                height: TILE_SIZE.min(full_height - y),
+            });
+        }
+    }
+    regions
+}
+
+// PDFiumの所有スレッドで取り出した、PNGエンコード前のRGBA画像
+// エンコードは所有スレッドを占有しないよう、呼び出し元のワーカーで並列に行う
+pub struct RgbaBitmap {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl RgbaBitmap {
+    // BGRAのビットマップをRGBAに変換して取り出す
+    fn from_pdf_bitmap(bitmap: &PdfBitmap) -> Self {
+        let width = bitmap.width() as usize;
+        let height = bitmap.height() as usize;
+        let mut pixels = vec![0u8; width * height * 4];
+        
+        let bgra_data = bitmap.as_raw_bytes();
+        for i in 0..(width * height) {
+            let index = i * 4;
+            
+            // BGRAからRGBAに変換（BとRを交換）
+            pixels[index] = bgra_data[index + 2];     // R
+            pixels[index + 1] = bgra_data[index + 1]; // G
+            pixels[index + 2] = bgra_data[index];     // B
+            pixels[index + 3] = bgra_data[index + 3]; // A
+        }
+        
+        Self {
+            width: width as u32,
+            height: height as u32,
+            pixels,
+        }
+    }
     
-    let document = pdfium.load_pdf_from_file(pdf_path, None)?;
+    // PNGにエンコードし、カスタムスキーム経由で配信できるよう画像ストアに登録
+    pub fn encode(&self) -> Result<PageImage> {
+        let mut png_data = Vec::new();
+        {
+            use image::ImageEncoder;
+            let encoder = image::codecs::png::PngEncoder::new(&mut png_data);
+            encoder.write_image(&self.pixels, self.width, self.height, image::ExtendedColorType::Rgba8)?;
+        }
+        
+        Ok(PageImage::new(png_data))
+    }
+}
+
+// ページ全体のレンダリング結果のうち、PDFiumを必要とする部分（座標は回転前のページ上）
+pub struct RenderedPage {
+    bitmap: RgbaBitmap,
+    text_elements: Vec<TextElement>,
+    links: Vec<PdfLink>,
+    original_width: f32,
+    original_height: f32,
+}
+
+impl RenderedPage {
+    // 読む順番の解析、座標の回転、PNGエンコードを行って表示用のデータにする
+    fn into_page_data(self, page_index: usize, rotation: RotationAngle, scale: f32) -> Result<PdfPageData> {
+        let RenderedPage { bitmap, text_elements, links, original_width, original_height } = self;
+        
+        // 行・段落・段組みを解析して読む順番に並べ替え（重ねて描画された単語も除く）
+        let text_elements = arrange_in_reading_order(text_elements);
+        
+        // テキストとリンクの座標を回転後のページ上の座標に変換
+        let text_elements = text_elements
+            .into_iter()
+            .map(|mut element| {
+                element.bounds = element.bounds.rotated(rotation, original_width, original_height);
+                element
+            })
+            .collect();
+        let links = links
+            .into_iter()
+            .map(|mut link| {
+                link.bounds = link.bounds.rotated(rotation, original_width, original_height);
+                link
+            })
+            .collect();
+        
+        // 回転を考慮した最終的なページ寸法
+        let (final_width, final_height) = match rotation {
+            RotationAngle::Rotate90 | RotationAngle::Rotate270 => (original_height, original_width),
+            _ => (original_width, original_height),
+        };
+        
+        Ok(PdfPageData {
+            image_data: bitmap.encode()?,
+            text_elements,
+            page_width: final_width,
+            page_height: final_height,
+            page_index,
+            rotation,
+            render_scale: scale,
+            links,
+        })
+    }
+}
+
+pub fn render_pdf_page_with_text(pdf_path: &str, page_index: usize, rotation: RotationAngle, scale: f32) -> Result<PdfPageData> {
+    let rendered = with_pdf_document(pdf_path, move |document| render_document_page(document, page_index, rotation, scale))?;
+    rendered.into_page_data(page_index, rotation, scale)
+}
+
+pub fn render_document_page(document: &PdfDocument, page_index: usize, rotation: RotationAngle, scale: f32) -> Result<RenderedPage> {
     let page = document.pages().get(page_index as u16)?;
     
     // ページの元の寸法を取得
     let original_width = page.width().value;
     let original_height = page.height().value;
     
-    // 回転を考慮したレンダリング設定
+    // タイル表示するページは、全体像として低解像度のプレビューだけをレンダリングする
+    // （render_scaleには要求された倍率を記録し、細部はタイルで補う）
+    let image_scale = if needs_tiled_rendering(original_width, original_height, scale) {
+        scale.min((TILED_PREVIEW_MAX_PIXELS / (original_width * original_height)).sqrt())
+    } else {
+        scale
+    };
+    
+    // 表示倍率に合わせたレンダリング設定
     let render_config = PdfRenderConfig::new()
-        .set_target_width(1000)
-        .set_maximum_height(1400)
+        .scale_page_by_factor(image_scale)
+        .set_maximum_width(MAX_RENDER_DIMENSION)
+        .set_maximum_height(MAX_RENDER_DIMENSION)
         .rotate_if_landscape(PdfPageRenderRotation::None, false);
     
-    // ページをレンダリング（初回は回転なし）
-    
-    // 手動で回転を含むレンダリング設定を作成
+    // 手動で回転を含むレンダリング設定を作成（90/270度では出力サイズの縦横も入れ替える）
     let render_config = match rotation {
-        RotationAngle::Rotate90 => render_config.rotate(PdfPageRenderRotation::Degrees90, false),
-        RotationAngle::Rotate180 => render_config.rotate(PdfPageRenderRotation::Degrees180, false),
-        RotationAngle::Rotate270 => render_config.rotate(PdfPageRenderRotation::Degrees270, false),
+        RotationAngle::Rotate90 => render_config.rotate(PdfPageRenderRotation::Degrees90, true),
+        RotationAngle::Rotate180 => render_config.rotate(PdfPageRenderRotation::Degrees180, true),
+        RotationAngle::Rotate270 => render_config.rotate(PdfPageRenderRotation::Degrees270, true),
         RotationAngle::None => render_config,
     };
 
     // 再レンダリング（回転込み）
     let bitmap = page.render_with_config(&render_config)?;
     
-    // BGRAからRGBAに変換
-    let width = bitmap.width() as usize;
-    let height = bitmap.height() as usize;
-    let mut rgba_data = vec![0u8; width * height * 4];
+    // テキスト抽出（単語単位、左上原点のページ座標）
+    let text_page = page.text()?;
+    let text_elements = extract_text_elements(&text_page, original_height);
     
-    let bgra_data = bitmap.as_raw_bytes();
-    for i in 0..(width * height) {
-        let bgra_idx = i * 4;
-        let rgba_idx = i * 4;
-        
-        // BGRAからRGBAに変換（BとRを交換）
-        rgba_data[rgba_idx] = bgra_data[bgra_idx + 2];     // R
-        rgba_data[rgba_idx + 1] = bgra_data[bgra_idx + 1]; // G
-        rgba_data[rgba_idx + 2] = bgra_data[bgra_idx];     // B
-        rgba_data[rgba_idx + 3] = bgra_data[bgra_idx + 3]; // A
-    }
+    // リンク注釈（左上原点のページ座標）
+    let links = extract_links(document, &page, original_height);
     
-    // PNGエンコード
-    let png_data = {
-        let mut png_data = Vec::new();
-        {
-            use image::ImageEncoder;
-            let encoder = image::codecs::png::PngEncoder::new(&mut png_data);
-            encoder.write_image(&rgba_data, width as u32, height as u32, image::ExtendedColorType::Rgba8)?;
-        }
-        png_data
+    Ok(RenderedPage {
+        bitmap: RgbaBitmap::from_pdf_bitmap(&bitmap),
+        text_elements,
+        links,
+        original_width,
+        original_height,
+    })
+}
+
+pub fn render_pdf_thumbnail(pdf_path: &str, page_index: usize, rotation: RotationAngle) -> Result<PageImage> {
+    with_pdf_document(pdf_path, move |document| render_document_thumbnail(document, page_index, rotation))?.encode()
+}
+
+// サムネイル用の低解像度レンダリング（テキスト抽出は行わない）
+pub fn render_document_thumbnail(document: &PdfDocument, page_index: usize, rotation: RotationAngle) -> Result<RgbaBitmap> {
+    let page = document.pages().get(page_index as u16)?;
+    
+    let rotated_width = match rotation {
+        RotationAngle::Rotate90 | RotationAngle::Rotate270 => page.height().value,
+        _ => page.width().value,
+    };
+    
+    let render_config = PdfRenderConfig::new()
+        .scale_page_by_factor(THUMBNAIL_WIDTH / rotated_width)
+        .set_maximum_width(MAX_RENDER_DIMENSION)
+        .set_maximum_height(MAX_RENDER_DIMENSION);
+    let render_config = match rotation {
+        RotationAngle::Rotate90 => render_config.rotate(PdfPageRenderRotation::Degrees90, true),
+        RotationAngle::Rotate180 => render_config.rotate(PdfPageRenderRotation::Degrees180, true),
+        RotationAngle::Rotate270 => render_config.rotate(PdfPageRenderRotation::Degrees270, true),
+        RotationAngle::None => render_config,
+    };
+    
+    let bitmap = page.render_with_config(&render_config)?;
+    Ok(RgbaBitmap::from_pdf_bitmap(&bitmap))
+}
+
+pub fn render_pdf_link_preview(pdf_path: &str, page_index: usize, rotation: RotationAngle, x: Option<f32>, y: Option<f32>) -> Result<PageImage> {
+    with_pdf_document(pdf_path, move |document| render_document_link_preview(document, page_index, rotation, x, y))?.encode()
+}
+
+// リンク先の周辺だけを切り出した低解像度のプレビュー（x, yはページ左上からの位置、回転前のポイント単位）
+// ページは表示と同じ向きに回転してから切り出す
+pub fn render_document_link_preview(document: &PdfDocument, page_index: usize, rotation: RotationAngle, x: Option<f32>, y: Option<f32>) -> Result<RgbaBitmap> {
+    let page_size = document.pages().page_size(page_index as u16)?;
+    let (scale, region) = link_preview_region(page_size.width().value, page_size.height().value, rotation, x, y);
+    
+    let (bitmap, _) = render_document_region(document, page_index, rotation, scale, region)?;
+    Ok(bitmap)
+}
+
+// リンク先プレビューの倍率と切り出す範囲（回転後のページ全体をその倍率でレンダリングした場合のピクセル座標）
+// ページの寸法と移動先は回転前のもの
+pub fn link_preview_region(page_width: f32, page_height: f32, rotation: RotationAngle, x: Option<f32>, y: Option<f32>) -> (f32, TileRegion) {
+    let (rotated_width, rotated_height) = match rotation {
+        RotationAngle::Rotate90 | RotationAngle::Rotate270 => (page_height, page_width),
+        _ => (page_width, page_height),
     };
+    let scale = LINK_PREVIEW_WIDTH as f32 / rotated_width;
+    let (full_width, full_height) = full_page_pixel_size(rotated_width, rotated_height, scale);
     
-    let base64_data = base64::engine::general_purpose::STANDARD.encode(&png_data);
-    let data_url = format!("data:image/png;base64,{}", base64_data);
-    
-    // テキスト抽出
-    let _text_page = page.text()?;
-    let mut text_elements = Vec::new();
-    
-    // Simplified text extraction - API needs investigation
-    // For now, just create a placeholder text element
-    text_elements.push(TextElement {
-        text: "Text extraction needs API update".to_string(),
-        bounds: TextBounds {
-            x: 0.0,
-            y: 0.0,
-            width: 100.0,
-            height: 12.0,
-        },
-        font_size: 12.0,
-    });
-    
-    // 重複テキストをフィルタリング
-    text_elements = filter_overlapping_text(text_elements, page_index);
-    
-    // 回転を考慮した最終的なページ寸法
-    let (final_width, final_height) = match rotation {
+    // 移動先の点（xの指定がなければ左右の中央）を表示上の位置に変換し、その少し上から切り出す
+    // 位置の指定がない場合はページの先頭
+    let top = if x.is_none() && y.is_none() {
+        0
+    } else {
+        let (_, displayed_y) = rotation.rotate_fraction(x.map_or(0.5, |x| x / page_width), y.map_or(0.0, |y| y / page_height));
+        ((displayed_y.clamp(0.0, 1.0) * rotated_height - 20.0).max(0.0) * scale) as u32
+    };
+    let top = top.min(full_height.saturating_sub(LINK_PREVIEW_MAX_HEIGHT));
+    let region = TileRegion {
+        x: 0,
+        y: top,
+        width: LINK_PREVIEW_WIDTH.min(full_width),
+        height: LINK_PREVIEW_MAX_HEIGHT.min(full_height - top),
+    };
+    
+    (scale, region)
+}
+
+pub fn render_pdf_page_region(pdf_path: &str, page_index: usize, rotation: RotationAngle, scale: f32, region: TileRegion) -> Result<PdfTileData> {
+    let (bitmap, (full_width, full_height)) =
+        with_pdf_document(pdf_path, move |document| render_document_region(document, page_index, rotation, scale, region))?;
+    
+    Ok(PdfTileData {
+        image_data: bitmap.encode()?,
+        key: TileKey::new(page_index, rotation, scale, region),
+        full_width,
+        full_height,
+    })
+}
+
+// ページの一部の矩形領域だけを指定倍率でレンダリング（ビットマップはタイルの大きさ分しか確保しない）
+// ページ全体をこの倍率でレンダリングした場合のピクセル寸法も返す
+pub fn render_document_region(document: &PdfDocument, page_index: usize, rotation: RotationAngle, scale: f32, region: TileRegion) -> Result<(RgbaBitmap, (u32, u32))> {
+    let page = document.pages().get(page_index as u16)?;
+    
+    let original_width = page.width().value;
+    let original_height = page.height().value;
+    let (rotated_width, rotated_height) = match rotation {
         RotationAngle::Rotate90 | RotationAngle::Rotate270 => (original_height, original_width),
         _ => (original_width, original_height),
     };
+    let (full_width, full_height) = full_page_pixel_size(rotated_width, rotated_height, scale);
     
-    Ok(PdfPageData {
-        image_data: data_url,
-        text_elements,
-        page_width: final_width,
-        page_height: final_height,
-        page_index,
-        rotation,
-    })
-}
\ No newline at end of file
+    // ページ座標（左上原点・ポイント単位）からタイル内のピクセル座標への変換行列
+    // 回転・拡大してから、タイルの左上が原点になるよう平行移動する
+    let tile_x = region.x as f32;
+    let tile_y = region.y as f32;
+    let (a, b, c, d, e, f) = match rotation {
+        RotationAngle::None => (scale, 0.0, 0.0, scale, -tile_x, -tile_y),
+        RotationAngle::Rotate90 => (0.0, scale, -scale, 0.0, scale * original_height - tile_x, -tile_y),
+        RotationAngle::Rotate180 => (-scale, 0.0, 0.0, -scale, scale * original_width - tile_x, scale * original_height - tile_y),
+        RotationAngle::Rotate270 => (0.0, -scale, scale, 0.0, -tile_x, scale * original_width - tile_y),
+    };
+    
+    // 出力の大きさは固定サイズで指定する（目標サイズ指定では自動の拡大が変換行列に掛け合わされ、タイルが歪む）
+    let tile_width = region.width as i32;
+    let tile_height = region.height as i32;
+    let render_config = PdfRenderConfig::new()
+        .set_fixed_size(tile_width, tile_height)
+        .transform(a, b, c, d, e, f)?
+        .clip(0, 0, tile_width, tile_height);
+    
+    let mut bitmap = PdfBitmap::empty(tile_width, tile_height, PdfBitmapFormat::BGRA, document.bindings())?;
+    page.render_into_bitmap_with_config(&mut bitmap, &render_config)?;
+    
+    Ok((RgbaBitmap::from_pdf_bitmap(&bitmap), (full_width, full_height)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::{get_pdfium_library_path, with_pdfium};
+
+    // 2つの画像で、色が大きく異なる画素の数
+    fn differing_pixels(a: &RgbaBitmap, b: &RgbaBitmap) -> usize {
+        a.pixels
+            .chunks_exact(4)
+            .zip(b.pixels.chunks_exact(4))
+            .filter(|(pa, pb)| pa.iter().zip(pb.iter()).any(|(ca, cb)| ca.abs_diff(*cb) > 32))
+            .count()
+    }
+
+    #[test]
+    fn link_preview_contains_destination() {
+        let (page_width, page_height) = (612.0, 792.0);
+        for y in [0.0, 15.0, 100.0, 396.0, 700.0, 790.0] {
+            let (scale, region) = link_preview_region(page_width, page_height, RotationAngle::None, None, Some(y));
+            let destination = y * scale;
+            assert!(region.y as f32 <= destination, "y = {}", y);
+            assert!(destination <= (region.y + region.height) as f32, "y = {}", y);
+            assert!(region.height <= LINK_PREVIEW_MAX_HEIGHT);
+            assert_eq!(region.width, LINK_PREVIEW_WIDTH);
+        }
+
+        // 位置の指定がなければページの先頭
+        let (_, region) = link_preview_region(page_width, page_height, RotationAngle::None, None, None);
+        assert_eq!(region.y, 0);
+    }
+
+    #[test]
+    fn link_preview_of_rotated_page_contains_destination() {
+        let (page_width, page_height) = (612.0, 792.0);
+        let rotations = [RotationAngle::Rotate90, RotationAngle::Rotate180, RotationAngle::Rotate270];
+        for rotation in rotations {
+            let (rotated_width, rotated_height) = match rotation {
+                RotationAngle::Rotate180 => (page_width, page_height),
+                _ => (page_height, page_width),
+            };
+            for (x, y) in [(30.0, 40.0), (300.0, 400.0), (580.0, 700.0), (100.0, 790.0)] {
+                let (scale, region) = link_preview_region(page_width, page_height, rotation, Some(x), Some(y));
+                assert_eq!(region.width, LINK_PREVIEW_WIDTH);
+                assert!((rotated_width * scale - LINK_PREVIEW_WIDTH as f32).abs() < 0.5);
+
+                // 移動先の点の、回転後のページ上でのピクセル位置
+                let (_, displayed_y) = rotation.rotate_fraction(x / page_width, y / page_height);
+                let destination = displayed_y * rotated_height * scale;
+                assert!(region.y as f32 <= destination, "{:?} ({}, {})", rotation, x, y);
+                assert!(destination <= (region.y + region.height) as f32, "{:?} ({}, {})", rotation, x, y);
+            }
+        }
+
+        // 90度回転したページでは、元のページの左端ほど表示上の下になる
+        let (_, left) = link_preview_region(page_width, page_height, RotationAngle::Rotate90, Some(20.0), Some(400.0));
+        let (_, right) = link_preview_region(page_width, page_height, RotationAngle::Rotate90, Some(590.0), Some(400.0));
+        assert!(left.y < right.y);
+    }
+
+    // 切り出したプレビューが、ページ全体のレンダリングの同じ範囲と一致すること
+    #[test]
+    fn link_preview_matches_same_region_of_full_page() {
+        if get_pdfium_library_path().is_err() {
+            eprintln!("PDFiumライブラリが見つからないため省略します");
+            return;
+        }
+
+        let (full_page, preview, region) = with_pdfium(|service| {
+            let mut document = service.pdfium().create_new_pdf()?;
+            let mut page = document
+                .pages_mut()
+                .create_page_at_end(PdfPagePaperSize::Custom(PdfPoints::new(240.0), PdfPoints::new(600.0)))?;
+            // 移動先（上から400ポイント）付近の矩形
+            page.objects_mut().create_path_object_rect(
+                PdfRect::new_from_values(160.0, 30.0, 200.0, 120.0),
+                None,
+                None,
+                Some(PdfColor::new(0, 0, 0, 255)),
+            )?;
+
+            let (scale, region) = link_preview_region(240.0, 600.0, RotationAngle::None, None, Some(400.0));
+            let full_page = render_document_page(&document, 0, RotationAngle::None, scale)?.bitmap;
+            let preview = render_document_link_preview(&document, 0, RotationAngle::None, None, Some(400.0))?;
+            Ok((full_page, preview, region))
+        })
+        .unwrap();
+
+        assert_eq!((preview.width, preview.height), (region.width, region.height));
+        let row_bytes = (full_page.width * 4) as usize;
+        let mut cropped = Vec::with_capacity(preview.pixels.len());
+        for row in region.y..region.y + region.height {
+            let start = row as usize * row_bytes + region.x as usize * 4;
+            cropped.extend_from_slice(&full_page.pixels[start..start + region.width as usize * 4]);
+        }
+        let cropped = RgbaBitmap { width: region.width, height: region.height, pixels: cropped };
+        let pixel_count = (region.width * region.height) as usize;
+        assert!(differing_pixels(&cropped, &preview) <= pixel_count / 100);
+    }
+
+    #[test]
+    fn tile_covering_whole_page_matches_full_page_render() {
+        if get_pdfium_library_path().is_err() {
+            eprintln!("PDFiumライブラリが見つからないため省略します");
+            return;
+        }
+
+        let renders = with_pdfium(|service| {
+            let mut document = service.pdfium().create_new_pdf()?;
+            let mut page = document
+                .pages_mut()
+                .create_page_at_end(PdfPagePaperSize::Custom(PdfPoints::new(200.0), PdfPoints::new(300.0)))?;
+            // 片側に寄せた矩形（拡大や縦横比がずれると位置が変わる）
+            page.objects_mut().create_path_object_rect(
+                PdfRect::new_from_values(200.0, 20.0, 280.0, 90.0),
+                None,
+                None,
+                Some(PdfColor::new(0, 0, 0, 255)),
+            )?;
+
+            let mut renders = Vec::new();
+            for rotation in [RotationAngle::None, RotationAngle::Rotate90, RotationAngle::Rotate180, RotationAngle::Rotate270] {
+                let full_page = render_document_page(&document, 0, rotation, 1.0)?.bitmap;
+                let region = TileRegion { x: 0, y: 0, width: full_page.width, height: full_page.height };
+                let (tile, full_size) = render_document_region(&document, 0, rotation, 1.0, region)?;
+                renders.push((rotation, full_page, tile, full_size));
+            }
+            Ok(renders)
+        })
+        .unwrap();
+
+        for (rotation, full_page, tile, full_size) in renders {
+            assert_eq!((tile.width, tile.height), (full_page.width, full_page.height), "{:?}", rotation);
+            assert_eq!(full_size, (full_page.width, full_page.height), "{:?}", rotation);
+            // 縁のアンチエイリアスの差は許容する
+            let pixel_count = (full_page.width * full_page.height) as usize;
+            assert!(differing_pixels(&full_page, &tile) <= pixel_count / 100, "{:?}", rotation);
+        }
+    }
+}
@@ -0,0 +1,238 @@
+use anyhow::Result;
+use regex::RegexBuilder;
+use crate::types::{SearchMatch, SearchOptions, TextBounds, TextElement};
+use crate::pdf::{with_pdf_document, extract_text_elements, arrange_in_reading_order};
+
+// 結果一覧に表示する前後の文字数
+const SNIPPET_CONTEXT_CHARS: usize = 30;
+
+// 検索が極端に遅くならないよう、結果の件数に上限を設ける
+const MAX_SEARCH_MATCHES: usize = 5000;
+
+// 全ページのテキストを単語単位で、読む順番に抽出
+// PDFiumの所有スレッドには1ページずつ依頼し、長い文書でも表示中のページのレンダリングを長く待たせない
+// 読む順番の解析は所有スレッドの外で行う
+pub fn extract_document_text(pdf_path: &str) -> Result<Vec<Vec<TextElement>>> {
+    let page_count = with_pdf_document(pdf_path, |document| Ok(document.pages().len() as usize))?;
+
+    let mut pages = Vec::with_capacity(page_count);
+    for page_index in 0..page_count {
+        let elements = with_pdf_document(pdf_path, move |document| {
+            let page = document.pages().get(page_index as u16)?;
+            let text_page = page.text()?;
+            Ok(extract_text_elements(&text_page, page.height().value))
+        })?;
+        pages.push(arrange_in_reading_order(elements));
+    }
+    Ok(pages)
+}
+
+// ページのテキスト（単語を空白で連結したもの）と、各単語の開始位置（バイト単位）
+fn page_text(elements: &[TextElement]) -> (String, Vec<usize>) {
+    let mut text = String::new();
+    let mut offsets = Vec::with_capacity(elements.len());
+    for element in elements {
+        if !text.is_empty() {
+            text.push(' ');
+        }
+        offsets.push(text.len());
+        text.push_str(&element.text);
+    }
+    (text, offsets)
+}
+
+pub fn search_document(pages: &[Vec<TextElement>], query: &str, options: &SearchOptions) -> Result<Vec<SearchMatch>> {
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let pattern = if options.use_regex { query.to_string() } else { regex::escape(query) };
+    let pattern = if options.whole_word { format!(r"\b(?:{})\b", pattern) } else { pattern };
+    let regex = RegexBuilder::new(&pattern)
+        .case_insensitive(!options.case_sensitive)
+        .build()?;
+
+    let mut matches = Vec::new();
+    for (page_index, elements) in pages.iter().enumerate() {
+        let (text, offsets) = page_text(elements);
+
+        for found in regex.find_iter(&text) {
+            if found.start() == found.end() {
+                continue; // 空文字列に一致するパターンは無視
+            }
+
+            let rects = match_rects(elements, &offsets, found.start(), found.end());
+            if rects.is_empty() {
+                continue;
+            }
+
+            matches.push(SearchMatch {
+                page_index,
+                rects,
+                snippet: snippet(&text, found.start(), found.end()),
+            });
+            if matches.len() >= MAX_SEARCH_MATCHES {
+                return Ok(matches);
+            }
+        }
+    }
+
+    Ok(matches)
+}
+
+// 一致した範囲にかかる単語ごとの矩形（単語の一部だけが一致した場合は文字数の比率で切り出す）
+fn match_rects(elements: &[TextElement], offsets: &[usize], start: usize, end: usize) -> Vec<TextBounds> {
+    let mut rects = Vec::new();
+
+    for (element, &offset) in elements.iter().zip(offsets) {
+        let element_end = offset + element.text.len();
+        if element_end <= start || offset >= end {
+            continue;
+        }
+
+        let char_count = element.text.chars().count().max(1) as f32;
+        let chars_before = element.text[..start.saturating_sub(offset).min(element.text.len())].chars().count() as f32;
+        let chars_until = element.text[..end.saturating_sub(offset).min(element.text.len())].chars().count() as f32;
+
+        // 縦書きの単語は上から下へ文字が並ぶ
+        if element.vertical {
+            let char_height = element.bounds.height / char_count;
+            rects.push(TextBounds {
+                x: element.bounds.x,
+                y: element.bounds.y + char_height * chars_before,
+                width: element.bounds.width,
+                height: char_height * (chars_until - chars_before),
+            });
+        } else {
+            let char_width = element.bounds.width / char_count;
+            rects.push(TextBounds {
+                x: element.bounds.x + char_width * chars_before,
+                y: element.bounds.y,
+                width: char_width * (chars_until - chars_before),
+                height: element.bounds.height,
+            });
+        }
+    }
+
+    rects
+}
+
+fn snippet(text: &str, start: usize, end: usize) -> String {
+    let before: String = text[..start].chars().rev().take(SNIPPET_CONTEXT_CHARS).collect::<Vec<_>>().into_iter().rev().collect();
+    let after: String = text[end..].chars().take(SNIPPET_CONTEXT_CHARS).collect();
+    let prefix = if before.len() < start { "…" } else { "" };
+    let suffix = if end + after.len() < text.len() { "…" } else { "" };
+    format!("{}{}【{}】{}{}", prefix, before, &text[start..end], after, suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn element(text: &str, x: f32, y: f32, width: f32, height: f32) -> TextElement {
+        TextElement {
+            text: text.to_string(),
+            bounds: TextBounds { x, y, width, height },
+            font_size: height,
+            vertical: false,
+        }
+    }
+
+    // 1行目に「Hello world」、2行目に「Worldwide」が並ぶページ
+    fn sample_pages() -> Vec<Vec<TextElement>> {
+        vec![
+            vec![element("Hello", 0.0, 0.0, 50.0, 10.0), element("world", 60.0, 0.0, 50.0, 10.0)],
+            vec![element("Worldwide", 0.0, 20.0, 90.0, 10.0)],
+        ]
+    }
+
+    fn search(query: &str, options: SearchOptions) -> Vec<SearchMatch> {
+        search_document(&sample_pages(), query, &options).unwrap()
+    }
+
+    #[test]
+    fn ignores_case_unless_requested() {
+        let matches = search("WORLD", SearchOptions::default());
+        assert_eq!(matches.iter().map(|m| m.page_index).collect::<Vec<_>>(), vec![0, 1]);
+
+        let matches = search("World", SearchOptions { case_sensitive: true, ..Default::default() });
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].page_index, 1);
+    }
+
+    #[test]
+    fn whole_word_skips_partial_matches() {
+        assert_eq!(search("world", SearchOptions::default()).len(), 2);
+
+        let matches = search("world", SearchOptions { whole_word: true, ..Default::default() });
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].page_index, 0);
+    }
+
+    #[test]
+    fn reports_invalid_regex_and_escapes_plain_queries() {
+        let regex = SearchOptions { use_regex: true, ..Default::default() };
+        assert!(search_document(&sample_pages(), "(wor", &regex).is_err());
+        assert!(search_document(&sample_pages(), "(wor", &SearchOptions::default()).unwrap().is_empty());
+        assert_eq!(search("w.rld", regex).len(), 2);
+    }
+
+    #[test]
+    fn empty_query_matches_nothing() {
+        assert!(search("", SearchOptions::default()).is_empty());
+        assert!(search("x*", SearchOptions { use_regex: true, ..Default::default() }).is_empty());
+    }
+
+    #[test]
+    fn match_spanning_words_has_a_rect_per_word() {
+        let matches = search("lo wo", SearchOptions::default());
+        assert_eq!(matches.len(), 1);
+        assert_eq!(
+            matches[0].rects,
+            vec![
+                TextBounds { x: 30.0, y: 0.0, width: 20.0, height: 10.0 },
+                TextBounds { x: 60.0, y: 0.0, width: 20.0, height: 10.0 },
+            ]
+        );
+    }
+
+    #[test]
+    fn match_rects_split_multibyte_words_by_characters() {
+        let mut vertical = element("縦書き", 0.0, 0.0, 10.0, 30.0);
+        vertical.vertical = true;
+        let elements = vec![element("日本語", 0.0, 0.0, 30.0, 10.0), vertical];
+        let (text, offsets) = page_text(&elements);
+
+        let start = text.find("本").unwrap();
+        assert_eq!(
+            match_rects(&elements, &offsets, start, start + "本".len()),
+            vec![TextBounds { x: 10.0, y: 0.0, width: 10.0, height: 10.0 }]
+        );
+
+        // 縦書きの単語は縦方向に切り出す
+        let start = text.find("書き").unwrap();
+        assert_eq!(
+            match_rects(&elements, &offsets, start, start + "書き".len()),
+            vec![TextBounds { x: 0.0, y: 10.0, width: 10.0, height: 20.0 }]
+        );
+    }
+
+    #[test]
+    fn snippet_marks_match_and_truncates_long_context() {
+        let text = "Hello world";
+        assert_eq!(snippet(text, 6, 11), "Hello 【world】");
+
+        let long = format!("{}target{}", "a".repeat(40), "b".repeat(40));
+        let start = long.find("target").unwrap();
+        let expected = format!("…{}【target】{}…", "a".repeat(SNIPPET_CONTEXT_CHARS), "b".repeat(SNIPPET_CONTEXT_CHARS));
+        assert_eq!(snippet(&long, start, start + "target".len()), expected);
+    }
+
+    #[test]
+    fn snippet_counts_context_in_characters() {
+        let text = format!("{}検索{}", "あ".repeat(40), "い".repeat(5));
+        let start = text.find("検索").unwrap();
+        let expected = format!("…{}【検索】{}", "あ".repeat(SNIPPET_CONTEXT_CHARS), "い".repeat(5));
+        assert_eq!(snippet(&text, start, start + "検索".len()), expected);
+    }
+}
@@ -1,52 +1,322 @@
-use crate::types::TextElement;
-
-pub fn filter_overlapping_text(text_elements: Vec<TextElement>, _page_index: usize) -> Vec<TextElement> {
-    let mut filtered_elements = Vec::new();
-    
-    for element in text_elements {
-        let text = element.text.trim();
-        
-        // 空のテキストや意味のない文字列をスキップ
-        if text.is_empty() || text.len() < 2 {
-            continue;
+use std::ops::Range;
+use std::sync::OnceLock;
+use lindera::{DictionaryConfig, DictionaryKind, Mode, Tokenizer, TokenizerConfig};
+use pdfium_render::prelude::*;
+use crate::types::{TextElement, TextBounds};
+
+// 同梱の辞書による形態素解析器（初回使用時に読み込む。読み込めない場合はNone）
+static JAPANESE_TOKENIZER: OnceLock<Option<Tokenizer>> = OnceLock::new();
+static CHINESE_TOKENIZER: OnceLock<Option<Tokenizer>> = OnceLock::new();
+
+// 抽出中の単語（PDF座標系: 原点は左下）
+struct WordBuilder {
+    text: String,
+    left: f32,
+    right: f32,
+    bottom: f32,
+    top: f32,
+    font_size: f32,
+    char_count: usize,
+    vertical: bool,
+}
+
+impl WordBuilder {
+    fn new(ch: char, rect: &PdfRect, font_size: f32) -> Self {
+        Self {
+            text: ch.to_string(),
+            left: rect.left().value,
+            right: rect.right().value,
+            bottom: rect.bottom().value,
+            top: rect.top().value,
+            font_size,
+            char_count: 1,
+            vertical: false,
         }
-        
-        // 数字のみの短い文字列をスキップ（ページ番号など）
-        if text.len() <= 3 && text.chars().all(|c| c.is_numeric()) {
-            continue;
+    }
+
+    // 同じ行（縦書きでは同じ列）で隣接している文字かどうか
+    // 続けられる場合は縦書きとして続くかどうかを返す（方向は2文字目で決まる）
+    fn continuation(&self, rect: &PdfRect, font_size: f32) -> Option<bool> {
+        let max_gap = self.font_size.max(font_size) * 0.3;
+
+        if !self.vertical {
+            let center_y = (rect.bottom().value + rect.top().value) / 2.0;
+            let same_line = center_y >= self.bottom && center_y <= self.top;
+            let gap = rect.left().value - self.right;
+            if same_line && gap <= max_gap && rect.right().value > self.left {
+                return Some(false);
+            }
         }
-        
-        // 重複チェック: 同じテキストが既に存在するかチェック
-        let is_duplicate = filtered_elements.iter().any(|existing: &TextElement| {
-            let existing_text = existing.text.trim();
-            
-            // 完全一致
-            if existing_text == text {
-                return true;
+
+        if self.vertical || self.char_count == 1 {
+            let center_x = (rect.left().value + rect.right().value) / 2.0;
+            let same_column = center_x >= self.left && center_x <= self.right;
+            let gap = self.bottom - rect.top().value;
+            if same_column && gap <= max_gap && rect.bottom().value < self.bottom {
+                return Some(true);
             }
-            
-            // 一方が他方を含む場合（より長い方を保持）
-            if text.len() > existing_text.len() && text.contains(existing_text) {
-                return false; // 新しい要素の方が長いので、既存の要素を置き換える
+        }
+
+        None
+    }
+
+    fn push(&mut self, ch: char, rect: &PdfRect, font_size: f32, vertical: bool) {
+        self.text.push(ch);
+        self.char_count += 1;
+        self.vertical = vertical;
+        self.left = self.left.min(rect.left().value);
+        self.right = self.right.max(rect.right().value);
+        self.bottom = self.bottom.min(rect.bottom().value);
+        self.top = self.top.max(rect.top().value);
+        self.font_size = self.font_size.max(font_size);
+    }
+
+    // 左上原点のTextBounds座標系に変換
+    fn finish(self, page_height: f32) -> TextElement {
+        TextElement {
+            text: self.text,
+            bounds: TextBounds {
+                x: self.left,
+                y: page_height - self.top,
+                width: self.right - self.left,
+                height: self.top - self.bottom,
+            },
+            font_size: self.font_size,
+            vertical: self.vertical,
+        }
+    }
+}
+
+pub fn extract_text_elements(text_page: &PdfPageText, page_height: f32) -> Vec<TextElement> {
+    let mut text_elements = Vec::new();
+    let mut current: Option<WordBuilder> = None;
+
+    for text_char in text_page.chars().iter() {
+        let ch = match text_char.unicode_char() {
+            Some(ch) => ch,
+            None => continue,
+        };
+
+        // 空白・改行は単語の区切りとして扱う
+        if ch.is_whitespace() || ch.is_control() {
+            if let Some(word) = current.take() {
+                text_elements.push(word.finish(page_height));
             }
-            
-            if existing_text.len() > text.len() && existing_text.contains(text) {
-                return true; // 既存の要素の方が長いので、新しい要素をスキップ
+            continue;
+        }
+
+        // ルーズボックスは行の高さ全体を含むため選択範囲に適している
+        let rect = match text_char.loose_bounds().or_else(|_| text_char.tight_bounds()) {
+            Ok(rect) => rect,
+            Err(_) => continue,
+        };
+        if rect.width().value <= 0.0 || rect.height().value <= 0.0 {
+            continue;
+        }
+        let font_size = text_char.scaled_font_size().value;
+
+        let continuation = current.as_ref().and_then(|word| word.continuation(&rect, font_size));
+        match (current.as_mut(), continuation) {
+            (Some(word), Some(vertical)) => word.push(ch, &rect, font_size, vertical),
+            _ => {
+                if let Some(word) = current.take() {
+                    text_elements.push(word.finish(page_height));
+                }
+                current = Some(WordBuilder::new(ch, &rect, font_size));
             }
-            
-            false
-        });
-        
-        if !is_duplicate {
-            // より長いテキストで置き換える場合は、既存の短い要素を削除
-            filtered_elements.retain(|existing| {
-                let existing_text = existing.text.trim();
-                !(text.len() > existing_text.len() && text.contains(existing_text))
-            });
-            
-            filtered_elements.push(element);
-        }
-    }
-    
-    filtered_elements
-}
\ No newline at end of file
+        }
+    }
+
+    if let Some(word) = current.take() {
+        text_elements.push(word.finish(page_height));
+    }
+
+    text_elements
+}
+
+fn bundled_tokenizer(kind: DictionaryKind) -> Option<Tokenizer> {
+    let config = TokenizerConfig {
+        dictionary: DictionaryConfig { kind: Some(kind), path: None },
+        user_dictionary: None,
+        mode: Mode::Normal,
+    };
+
+    match Tokenizer::from_config(config) {
+        Ok(tokenizer) => Some(tokenizer),
+        Err(e) => {
+            eprintln!("形態素解析の辞書を読み込めませんでした: {}", e);
+            None
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum CharClass {
+    Kanji,
+    Hiragana,
+    Katakana,
+    Alphanumeric,
+    Other,
+}
+
+fn char_class(c: char) -> CharClass {
+    match c {
+        '\u{3040}'..='\u{309F}' => CharClass::Hiragana,
+        '\u{30A0}'..='\u{30FF}' | '\u{FF66}'..='\u{FF9F}' => CharClass::Katakana,
+        '\u{3400}'..='\u{4DBF}' | '\u{4E00}'..='\u{9FFF}' | '\u{F900}'..='\u{FAFF}' | '々' => CharClass::Kanji,
+        c if c.is_alphanumeric() => CharClass::Alphanumeric,
+        _ => CharClass::Other,
+    }
+}
+
+// 単語の境界（バイト位置の範囲）
+// 仮名を含むテキストは日本語、漢字のみのテキストは中国語の辞書で分割する
+pub fn segment_words(text: &str) -> Vec<Range<usize>> {
+    let has_kana = text.chars().any(|c| matches!(char_class(c), CharClass::Hiragana | CharClass::Katakana));
+    let has_kanji = text.chars().any(|c| char_class(c) == CharClass::Kanji);
+    let tokenizer = if has_kana {
+        JAPANESE_TOKENIZER.get_or_init(|| bundled_tokenizer(DictionaryKind::IPADIC)).as_ref()
+    } else if has_kanji {
+        CHINESE_TOKENIZER.get_or_init(|| bundled_tokenizer(DictionaryKind::CcCedict)).as_ref()
+    } else {
+        None
+    };
+
+    match tokenizer.and_then(|tokenizer| tokenizer.tokenize(text).ok()) {
+        Some(tokens) => tokens.iter().map(|token| token.byte_start..token.byte_end).collect(),
+        None => segment_by_char_class(text),
+    }
+}
+
+// 辞書が使えない場合は文字の種類が変わる位置で区切る
+fn segment_by_char_class(text: &str) -> Vec<Range<usize>> {
+    let mut words: Vec<Range<usize>> = Vec::new();
+    let mut previous_class = None;
+    for (offset, c) in text.char_indices() {
+        let class = char_class(c);
+        match words.last_mut() {
+            Some(word) if previous_class == Some(class) && class != CharClass::Other => word.end = offset + c.len_utf8(),
+            _ => words.push(offset..offset + c.len_utf8()),
+        }
+        previous_class = Some(class);
+    }
+    words
+}
+
+// 指定した文字位置を含む単語の範囲（文字単位）
+pub fn word_at(text: &str, char_offset: usize) -> Option<Range<usize>> {
+    word_containing(text, &segment_words(text), char_offset)
+}
+
+// 単語の境界（バイト位置）から、文字位置を含む単語を文字単位の範囲で返す
+fn word_containing(text: &str, words: &[Range<usize>], char_offset: usize) -> Option<Range<usize>> {
+    let (byte_offset, _) = text.char_indices().nth(char_offset)?;
+    let word = words.iter().find(|word| word.contains(&byte_offset))?;
+    Some(text[..word.start].chars().count()..text[..word.end].chars().count())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // PDF座標系（原点は左下）の1文字分の矩形
+    fn rect(left: f32, bottom: f32, size: f32) -> PdfRect {
+        PdfRect::new_from_values(bottom, left, bottom + size, left + size)
+    }
+
+    #[test]
+    fn continues_horizontally_on_the_same_line() {
+        let word = WordBuilder::new('a', &rect(100.0, 500.0, 10.0), 10.0);
+        assert_eq!(word.continuation(&rect(110.0, 500.0, 10.0), 10.0), Some(false));
+        assert_eq!(word.continuation(&rect(112.0, 501.0, 10.0), 10.0), Some(false));
+        // 文字サイズの3割を超える間隔や、別の行の文字は続けない
+        assert_eq!(word.continuation(&rect(114.0, 500.0, 10.0), 10.0), None);
+        assert_eq!(word.continuation(&rect(110.0, 520.0, 10.0), 10.0), None);
+    }
+
+    #[test]
+    fn second_character_below_starts_a_vertical_word() {
+        let mut word = WordBuilder::new('縦', &rect(300.0, 700.0, 10.0), 10.0);
+        assert_eq!(word.continuation(&rect(300.0, 690.0, 10.0), 10.0), Some(true));
+        word.push('書', &rect(300.0, 690.0, 10.0), 10.0, true);
+        assert_eq!(word.continuation(&rect(300.5, 679.0, 10.0), 10.0), Some(true));
+        word.push('き', &rect(300.5, 679.0, 10.0), 10.0, true);
+
+        // 縦書きの単語は右隣の文字や上の文字では続けない
+        assert_eq!(word.continuation(&rect(310.0, 679.0, 10.0), 10.0), None);
+        assert_eq!(word.continuation(&rect(300.0, 710.0, 10.0), 10.0), None);
+
+        let element = word.finish(842.0);
+        assert!(element.vertical);
+        assert_eq!(element.text, "縦書き");
+        assert_eq!(element.bounds, TextBounds { x: 300.0, y: 132.0, width: 10.5, height: 31.0 });
+    }
+
+    #[test]
+    fn horizontal_word_does_not_turn_vertical() {
+        let mut word = WordBuilder::new('a', &rect(100.0, 500.0, 10.0), 10.0);
+        word.push('b', &rect(110.0, 500.0, 10.0), 10.0, false);
+        assert_eq!(word.continuation(&rect(110.0, 490.0, 10.0), 10.0), None);
+        assert!(!word.finish(842.0).vertical);
+    }
+
+    fn words<'a>(text: &'a str, ranges: &[Range<usize>]) -> Vec<&'a str> {
+        ranges.iter().map(|range| &text[range.clone()]).collect()
+    }
+
+    #[test]
+    fn fallback_splits_where_the_character_class_changes() {
+        let text = "PDFを読む2024年版";
+        assert_eq!(words(text, &segment_by_char_class(text)), ["PDF", "を", "読", "む", "2024", "年版"]);
+        assert_eq!(words("カタカナとひらがな", &segment_by_char_class("カタカナとひらがな")), ["カタカナ", "とひらがな"]);
+        // 記号や空白は1文字ずつ区切る
+        assert_eq!(words("a, b", &segment_by_char_class("a, b")), ["a", ",", " ", "b"]);
+    }
+
+    #[test]
+    fn converts_character_offsets_to_character_ranges() {
+        let text = "PDFを読む2024年版";
+        let ranges = segment_by_char_class(text);
+        assert_eq!(word_containing(text, &ranges, 1), Some(0..3));
+        assert_eq!(word_containing(text, &ranges, 4), Some(4..5));
+        assert_eq!(word_containing(text, &ranges, 8), Some(6..10));
+        assert_eq!(word_containing(text, &ranges, 11), Some(10..12));
+        assert_eq!(word_containing(text, &ranges, 12), None);
+    }
+
+    #[test]
+    fn offset_at_a_word_boundary_selects_the_following_word() {
+        let text = "PDFを読む";
+        let ranges = segment_by_char_class(text);
+        // 「を」の直前（"PDF"の終わり）をダブルクリックした位置は「を」を選ぶ
+        assert_eq!(word_containing(text, &ranges, 3), Some(3..4));
+        assert_eq!(word_containing(text, &ranges, 2), Some(0..3));
+    }
+
+    #[test]
+    fn ascii_text_is_segmented_without_a_dictionary() {
+        assert_eq!(words("hello world", &segment_words("hello world")), ["hello", " ", "world"]);
+        assert_eq!(word_at("hello world", 6), Some(6..11));
+        assert_eq!(word_at("hello world", 5), Some(5..6));
+    }
+
+    #[test]
+    fn dictionary_segments_mixed_japanese_text() {
+        let text = "東京でPDFを読む";
+        let ranges = segment_words(text);
+
+        // 全ての範囲が文字の境界にあり、隙間なく並んでいる
+        let mut end = 0;
+        for range in &ranges {
+            assert_eq!(range.start, end);
+            assert!(text.is_char_boundary(range.start) && text.is_char_boundary(range.end));
+            assert!(range.start < range.end);
+            end = range.end;
+        }
+        assert_eq!(end, text.len());
+
+        assert_eq!(word_at(text, 1), Some(0..2));
+        assert_eq!(word_at(text, 4), Some(3..6));
+        assert_eq!(word_at(text, 100), None);
+    }
+}
@@ -1,6 +1,12 @@
 use anyhow::Result;
 use std::path::PathBuf;
-use pdfium_render::prelude::Pdfium;
+use pdfium_render::prelude::*;
+use crate::pdf::{with_pdf_document_async, has_document_password};
+use crate::types::{DocumentInfo, DocumentPermissions, OutlineItem};
+
+// 壊れたPDFで循環参照している場合に備えたアウトラインの上限
+const MAX_OUTLINE_DEPTH: usize = 32;
+const MAX_OUTLINE_ITEMS: usize = 10000;
 
 pub fn get_pdfium_library_path() -> Result<PathBuf> {
     // アプリケーションバンドル内のパスを最初に試す（.appファイル用）
@@ -60,15 +66,190 @@ pub fn get_pdfium_library_path() -> Result<PathBuf> {
     }
 }
 
-pub fn get_pdf_info(pdf_path: &str) -> Result<(usize, String)> {
-    let library_path = get_pdfium_library_path()?;
-    let pdfium = Pdfium::new(
-        Pdfium::bind_to_library(library_path)?
+pub async fn get_pdf_info(pdf_path: &str) -> Result<DocumentInfo> {
+    let file_size = std::fs::metadata(pdf_path)?.len();
+    let requires_password = has_document_password(pdf_path);
+    
+    with_pdf_document_async(pdf_path, move |document| {
+        let metadata = document.metadata();
+        let permissions = document.permissions();
+        let tag_value = |tag_type: PdfDocumentMetadataTagType| {
+            metadata
+                .get(tag_type)
+                .map(|tag| tag.value().trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        
+        Ok(DocumentInfo {
+            title: tag_value(PdfDocumentMetadataTagType::Title),
+            author: tag_value(PdfDocumentMetadataTagType::Author),
+            subject: tag_value(PdfDocumentMetadataTagType::Subject),
+            keywords: tag_value(PdfDocumentMetadataTagType::Keywords),
+            creator: tag_value(PdfDocumentMetadataTagType::Creator),
+            producer: tag_value(PdfDocumentMetadataTagType::Producer),
+            creation_date: tag_value(PdfDocumentMetadataTagType::CreationDate).map(|date| format_pdf_date(&date)),
+            modification_date: tag_value(PdfDocumentMetadataTagType::ModificationDate).map(|date| format_pdf_date(&date)),
+            pdf_version: format_pdf_version(document.version()),
+            page_count: document.pages().len().into(),
+            file_size,
+            is_encrypted: !matches!(permissions.security_handler_revision(), Ok(PdfSecurityHandlerRevision::Unprotected)),
+            requires_password,
+            permissions: DocumentPermissions {
+                can_print: permissions.can_print_high_quality().unwrap_or(true) || permissions.can_print_only_low_quality().unwrap_or(true),
+                can_copy: permissions.can_extract_text_and_graphics().unwrap_or(true),
+                can_modify: permissions.can_modify_document_content().unwrap_or(true),
+                can_annotate: permissions.can_add_or_modify_text_annotations().unwrap_or(true),
+            },
+        })
+    })
+    .await
+}
+
+fn format_pdf_version(version: PdfDocumentVersion) -> String {
+    match version {
+        PdfDocumentVersion::Unset => "不明".to_string(),
+        PdfDocumentVersion::Pdf1_0 => "1.0".to_string(),
+        PdfDocumentVersion::Pdf1_1 => "1.1".to_string(),
+        PdfDocumentVersion::Pdf1_2 => "1.2".to_string(),
+        PdfDocumentVersion::Pdf1_3 => "1.3".to_string(),
+        PdfDocumentVersion::Pdf1_4 => "1.4".to_string(),
+        PdfDocumentVersion::Pdf1_5 => "1.5".to_string(),
+        PdfDocumentVersion::Pdf1_6 => "1.6".to_string(),
+        PdfDocumentVersion::Pdf1_7 => "1.7".to_string(),
+        PdfDocumentVersion::Pdf2_0 => "2.0".to_string(),
+        PdfDocumentVersion::Other(version) => format!("{}.{}", version / 10, version % 10),
+    }
+}
+
+// PDFの日付文字列（D:YYYYMMDDHHmmSS+HH'mm'）を読みやすい形式に変換
+fn format_pdf_date(date: &str) -> String {
+    let digits = date.trim_start_matches("D:");
+    // 先頭8バイトが全てASCIIの数字であることを確かめてから切り出す（マルチバイト文字の途中で切らない）
+    if !digits.get(..8).is_some_and(|s| s.bytes().all(|b| b.is_ascii_digit())) {
+        return date.to_string();
+    }
+    
+    let part = |start: usize, end: usize, default: &'static str| digits.get(start..end).filter(|s| s.chars().all(|c| c.is_ascii_digit())).unwrap_or(default);
+    let mut formatted = format!(
+        "{}-{}-{} {}:{}:{}",
+        &digits[0..4], &digits[4..6], &digits[6..8],
+        part(8, 10, "00"), part(10, 12, "00"), part(12, 14, "00")
     );
     
-    let document = pdfium.load_pdf_from_file(pdf_path, None)?;
-    let page_count = document.pages().len();
-    let title = "Unknown PDF".to_string(); // Metadata API needs investigation
+    // タイムゾーン（Zまたは+HH'mm'）
+    match digits.get(14..15) {
+        Some("Z") => formatted.push_str(" UTC"),
+        Some(sign @ ("+" | "-")) => {
+            let zone: String = digits[15..].chars().filter(|c| c.is_ascii_digit()).collect();
+            if zone.len() >= 4 {
+                formatted.push_str(&format!(" {}{}:{}", sign, &zone[0..2], &zone[2..4]));
+            } else if zone.len() >= 2 {
+                formatted.push_str(&format!(" {}{}:00", sign, &zone[0..2]));
+            }
+        }
+        _ => {}
+    }
+    
+    formatted
+}
+
+// 全ページの元の寸法（ポイント単位、回転前）を取得
+pub async fn get_page_sizes(pdf_path: &str) -> Result<Vec<(f32, f32)>> {
+    with_pdf_document_async(pdf_path, |document| {
+        let sizes = document.pages().page_sizes()?
+            .iter()
+            .map(|rect| (rect.width().value, rect.height().value))
+            .collect();
+        
+        Ok(sizes)
+    })
+    .await
+}
+
+// 文書のアウトライン（しおり）をツリー構造で取得
+pub async fn get_document_outline(pdf_path: &str) -> Result<Vec<OutlineItem>> {
+    with_pdf_document_async(pdf_path, |document| {
+        let mut item_count = 0;
+        let outline = match document.bookmarks().root() {
+            Some(first) => collect_outline_siblings(first, 0, &mut item_count),
+            None => Vec::new(),
+        };
+        
+        Ok(outline)
+    })
+    .await
+}
+
+// 指定したしおりとその後ろの兄弟を、子を含めて変換
+fn collect_outline_siblings(first: PdfBookmark, depth: usize, item_count: &mut usize) -> Vec<OutlineItem> {
+    let mut items = Vec::new();
+    let mut current = Some(first);
+    
+    while let Some(bookmark) = current {
+        if *item_count >= MAX_OUTLINE_ITEMS {
+            break;
+        }
+        *item_count += 1;
+        
+        let children = match bookmark.first_child() {
+            Some(child) if depth + 1 < MAX_OUTLINE_DEPTH => collect_outline_siblings(child, depth + 1, item_count),
+            _ => Vec::new(),
+        };
+        
+        items.push(OutlineItem {
+            title: bookmark.title().unwrap_or_default().trim().to_string(),
+            page_index: bookmark_page_index(&bookmark),
+            children,
+        });
+        
+        current = bookmark.next_sibling();
+    }
+    
+    items
+}
+
+// しおりの移動先ページ（直接の移動先か、文書内への移動アクション）
+pub fn bookmark_page_index(bookmark: &PdfBookmark) -> Option<usize> {
+    if let Some(destination) = bookmark.destination() {
+        return destination.page_index().ok().map(|index| index as usize);
+    }
     
-    Ok((page_count.into(), title))
-}
\ No newline at end of file
+    match bookmark.action()? {
+        PdfAction::LocalDestination(action) => action.destination().ok()?.page_index().ok().map(|index| index as usize),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_complete_dates() {
+        assert_eq!(format_pdf_date("D:20240315123045+09'00'"), "2024-03-15 12:30:45 +09:00");
+        assert_eq!(format_pdf_date("D:20240315123045Z"), "2024-03-15 12:30:45 UTC");
+        assert_eq!(format_pdf_date("D:20240315123045-05"), "2024-03-15 12:30:45 -05:00");
+        assert_eq!(format_pdf_date("20240315123045"), "2024-03-15 12:30:45");
+    }
+
+    #[test]
+    fn fills_missing_time_fields() {
+        assert_eq!(format_pdf_date("D:20240315"), "2024-03-15 00:00:00");
+        assert_eq!(format_pdf_date("D:2024031512"), "2024-03-15 12:00:00");
+    }
+
+    #[test]
+    fn keeps_truncated_or_invalid_dates_as_is() {
+        assert_eq!(format_pdf_date("D:2024"), "D:2024");
+        assert_eq!(format_pdf_date(""), "");
+        assert_eq!(format_pdf_date("D:2024-03-15"), "D:2024-03-15");
+    }
+
+    #[test]
+    fn does_not_panic_on_non_ascii_dates() {
+        assert_eq!(format_pdf_date("D:２０２４年３月"), "D:２０２４年３月");
+        assert_eq!(format_pdf_date("D:2024031é"), "D:2024031é");
+        assert_eq!(format_pdf_date("D:2024031512年"), "2024-03-15 12:00:00");
+        assert_eq!(format_pdf_date("D:20240315123045+０９"), "2024-03-15 12:30:45");
+    }
+}
@@ -0,0 +1,341 @@
+use anyhow::Result;
+use std::ops::Range;
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
+use tokio::sync::oneshot;
+use crate::types::{PdfPageData, PdfTileData, RotationAngle, TextElement, TileRegion};
+use crate::pdf::{render_pdf_page_with_text, render_pdf_page_region, render_pdf_thumbnail, render_pdf_link_preview, extract_document_text, close_pdf_document, has_document_password, PageImage};
+use crate::storage::{load_cached_page, save_cached_page, load_cached_thumbnail, save_cached_thumbnail};
+
+// 表示中のページの前後何ページまでをレンダリング対象にするか
+pub const RENDER_WINDOW_BEHIND: usize = 4;
+pub const RENDER_WINDOW_AHEAD: usize = 8;
+
+// レンダリング対象（ページ全体か、ページの一部のタイルか）と結果の返信先
+enum RenderTarget {
+    Page(oneshot::Sender<Result<PdfPageData>>),
+    Tile(TileRegion, oneshot::Sender<Result<PdfTileData>>),
+    Thumbnail(oneshot::Sender<Result<PageImage>>),
+    LinkPreview(Option<f32>, Option<f32>, oneshot::Sender<Result<PageImage>>), // 移動先のx, y（回転はRenderJobのrotation）
+    DocumentText(oneshot::Sender<Result<Vec<Vec<TextElement>>>>),
+}
+
+// レンダリング要求（結果はoneshotチャネルで返す）
+struct RenderJob {
+    pdf_path: String,
+    page_index: usize,
+    rotation: RotationAngle,
+    scale: f32,
+    generation: u64, // ファイルを開くたびに更新される世代トークン
+    target: RenderTarget,
+}
+
+impl RenderJob {
+    fn is_cancelled(&self) -> bool {
+        match &self.target {
+            RenderTarget::Page(reply) => reply.is_closed(),
+            RenderTarget::Tile(_, reply) => reply.is_closed(),
+            RenderTarget::Thumbnail(reply) => reply.is_closed(),
+            RenderTarget::LinkPreview(_, _, reply) => reply.is_closed(),
+            RenderTarget::DocumentText(reply) => reply.is_closed(),
+        }
+    }
+
+    // 値が小さいほど優先して処理する
+    // リンク先プレビューはマウス操作に応答するため最優先、サムネイルはページ本体より後回し
+    fn priority(&self) -> u8 {
+        match self.target {
+            RenderTarget::LinkPreview(..) => 0,
+            RenderTarget::Page(_) | RenderTarget::Tile(..) | RenderTarget::DocumentText(_) => 1,
+            RenderTarget::Thumbnail(_) => 2,
+        }
+    }
+
+    // サムネイルやリンク先プレビュー、検索用のテキスト抽出は表示範囲とは関係なく要求されるため、範囲外でもキャンセルしない
+    fn ignores_view_window(&self) -> bool {
+        matches!(self.target, RenderTarget::Thumbnail(_) | RenderTarget::LinkPreview(..) | RenderTarget::DocumentText(_))
+    }
+
+    fn run(self) {
+        match self.target {
+            RenderTarget::Page(reply) => {
+                // パスワードで保護された文書は、復号した内容をディスクに残さない
+                let use_disk_cache = !has_document_password(&self.pdf_path);
+
+                // 以前にレンダリングした結果がディスクに残っていれば、PDFiumを使わずに返す
+                if use_disk_cache {
+                    if let Some(page_data) = load_cached_page(&self.pdf_path, self.page_index, self.rotation, self.scale) {
+                        let _ = reply.send(Ok(page_data));
+                        return;
+                    }
+                }
+
+                let result = render_pdf_page_with_text(&self.pdf_path, self.page_index, self.rotation, self.scale);
+                match &result {
+                    Ok(page_data) if use_disk_cache => {
+                        if let Err(e) = save_cached_page(&self.pdf_path, page_data) {
+                            eprintln!("Failed to save render cache for page {}: {}", self.page_index + 1, e);
+                        }
+                    }
+                    _ => {}
+                }
+                let _ = reply.send(result);
+            }
+            RenderTarget::Tile(region, reply) => {
+                let result = render_pdf_page_region(&self.pdf_path, self.page_index, self.rotation, self.scale, region);
+                let _ = reply.send(result);
+            }
+            RenderTarget::Thumbnail(reply) => {
+                let use_disk_cache = !has_document_password(&self.pdf_path);
+                if use_disk_cache {
+                    if let Some(thumbnail) = load_cached_thumbnail(&self.pdf_path, self.page_index, self.rotation) {
+                        let _ = reply.send(Ok(thumbnail));
+                        return;
+                    }
+                }
+
+                let result = render_pdf_thumbnail(&self.pdf_path, self.page_index, self.rotation);
+                match &result {
+                    Ok(thumbnail) if use_disk_cache => {
+                        if let Err(e) = save_cached_thumbnail(&self.pdf_path, self.page_index, self.rotation, thumbnail) {
+                            eprintln!("Failed to save thumbnail cache for page {}: {}", self.page_index + 1, e);
+                        }
+                    }
+                    _ => {}
+                }
+                let _ = reply.send(result);
+            }
+            RenderTarget::LinkPreview(x, y, reply) => {
+                let result = render_pdf_link_preview(&self.pdf_path, self.page_index, self.rotation, x, y);
+                let _ = reply.send(result);
+            }
+            RenderTarget::DocumentText(reply) => {
+                let result = extract_document_text(&self.pdf_path);
+                let _ = reply.send(result);
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct JobQueue {
+    jobs: Vec<RenderJob>,
+    focus_generation: u64,
+    focus_page: usize,
+}
+
+impl JobQueue {
+    // 優先度が高く、表示中のページに最も近い要求を取り出す
+    fn pop_nearest(&mut self) -> Option<RenderJob> {
+        let focus_page = self.focus_page;
+        let nearest = self
+            .jobs
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, job)| (job.priority(), job.page_index.abs_diff(focus_page)))
+            .map(|(index, _)| index)?;
+        Some(self.jobs.swap_remove(nearest))
+    }
+}
+
+// 専用スレッドでレンダリング要求を処理するワーカープール
+// PDFium自体はスレッドセーフではなく、pdfium-renderも全ての呼び出しを1つのロックで直列化するため、
+// ラスタライズだけはPDFiumの所有スレッド（document.rs）で1つずつ行う。
+// それ以外のディスクキャッシュの読み書き、PNGエンコード、テキストの読む順番の解析はワーカーごとに並列に行い、
+// UIスレッドはどの処理の完了も同期的に待たない
+pub struct RenderWorkerPool {
+    queue: Arc<(Mutex<JobQueue>, Condvar)>,
+}
+
+impl RenderWorkerPool {
+    fn new(worker_count: usize) -> Self {
+        let queue = Arc::new((Mutex::new(JobQueue::default()), Condvar::new()));
+
+        for worker_index in 0..worker_count {
+            let queue = Arc::clone(&queue);
+            let spawn_result = std::thread::Builder::new()
+                .name(format!("pdf-render-{}", worker_index))
+                .spawn(move || worker_loop(queue));
+            if let Err(e) = spawn_result {
+                eprintln!("Failed to spawn render worker {}: {}", worker_index, e);
+            }
+        }
+
+        Self { queue }
+    }
+
+    fn submit(&self, job: RenderJob) {
+        let (lock, condvar) = &*self.queue;
+        if let Ok(mut queue) = lock.lock() {
+            // 既に古くなった世代の要求は受け付けない（送信側を破棄してキャンセル扱いにする）
+            if job.generation < queue.focus_generation {
+                return;
+            }
+            queue.jobs.push(job);
+            condvar.notify_one();
+        }
+    }
+
+    // 優先度の基準となるページを更新し、古い世代や表示範囲外の要求をキャンセル
+    fn set_focus(&self, generation: u64, focus_page: usize, window: Range<usize>) {
+        let (lock, _) = &*self.queue;
+        if let Ok(mut queue) = lock.lock() {
+            queue.focus_generation = generation;
+            queue.focus_page = focus_page;
+            queue.jobs.retain(|job| job.generation == generation && (job.ignores_view_window() || window.contains(&job.page_index)));
+        }
+    }
+
+    // 閉じたドキュメントに対する、まだ処理していない要求を破棄
+    fn close_document(&self, pdf_path: &str) {
+        let (lock, _) = &*self.queue;
+        if let Ok(mut queue) = lock.lock() {
+            queue.jobs.retain(|job| job.pdf_path != pdf_path);
+        }
+    }
+}
+
+fn worker_loop(queue: Arc<(Mutex<JobQueue>, Condvar)>) {
+    let (lock, condvar) = &*queue;
+
+    loop {
+        let job = {
+            let mut queue = match lock.lock() {
+                Ok(queue) => queue,
+                Err(_) => return,
+            };
+            if queue.jobs.is_empty() {
+                // 新しい要求が届くまで待機
+                queue = match condvar.wait(queue) {
+                    Ok(queue) => queue,
+                    Err(_) => return,
+                };
+            }
+            queue.pop_nearest()
+        };
+
+        let Some(job) = job else { continue };
+
+        // 受信側が既に破棄されている（キャンセル済み）場合はレンダリングしない
+        if job.is_cancelled() {
+            continue;
+        }
+
+        job.run();
+    }
+}
+
+static RENDER_POOL: OnceLock<RenderWorkerPool> = OnceLock::new();
+
+fn render_pool() -> &'static RenderWorkerPool {
+    RENDER_POOL.get_or_init(|| {
+        // CPUコア数に基づいてワーカー数を決定
+        let worker_count = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4).min(8);
+        RenderWorkerPool::new(worker_count)
+    })
+}
+
+// ワーカープールでページをレンダリングし、完了を非同期に待つ
+pub async fn render_page_in_background(pdf_path: &str, page_index: usize, rotation: RotationAngle, scale: f32, generation: u64) -> Result<PdfPageData> {
+    let (reply, receiver) = oneshot::channel();
+    render_pool().submit(RenderJob {
+        pdf_path: pdf_path.to_string(),
+        page_index,
+        rotation,
+        scale,
+        generation,
+        target: RenderTarget::Page(reply),
+    });
+
+    receiver
+        .await
+        .map_err(|_| anyhow::anyhow!("ページ {} のレンダリングがキャンセルされました", page_index + 1))?
+}
+
+// ページの一部の領域（タイル）をワーカープールでレンダリング
+pub async fn render_tile_in_background(pdf_path: &str, page_index: usize, rotation: RotationAngle, scale: f32, region: TileRegion, generation: u64) -> Result<PdfTileData> {
+    let (reply, receiver) = oneshot::channel();
+    render_pool().submit(RenderJob {
+        pdf_path: pdf_path.to_string(),
+        page_index,
+        rotation,
+        scale,
+        generation,
+        target: RenderTarget::Tile(region, reply),
+    });
+
+    receiver
+        .await
+        .map_err(|_| anyhow::anyhow!("ページ {} のタイルのレンダリングがキャンセルされました", page_index + 1))?
+}
+
+// サムネイル用の低解像度画像をワーカープールでレンダリング
+pub async fn render_thumbnail_in_background(pdf_path: &str, page_index: usize, rotation: RotationAngle, generation: u64) -> Result<PageImage> {
+    let (reply, receiver) = oneshot::channel();
+    render_pool().submit(RenderJob {
+        pdf_path: pdf_path.to_string(),
+        page_index,
+        rotation,
+        scale: 0.0, // サムネイルは幅を固定してレンダリングするため使用しない
+        generation,
+        target: RenderTarget::Thumbnail(reply),
+    });
+
+    receiver
+        .await
+        .map_err(|_| anyhow::anyhow!("ページ {} のサムネイルのレンダリングがキャンセルされました", page_index + 1))?
+}
+
+// リンク先の周辺を切り出したプレビュー画像をワーカープールでレンダリング
+// rotationには移動先のページの表示上の回転を渡し、表示と同じ向きで切り出す
+pub async fn render_link_preview_in_background(
+    pdf_path: &str,
+    page_index: usize,
+    rotation: RotationAngle,
+    x: Option<f32>,
+    y: Option<f32>,
+    generation: u64,
+) -> Result<PageImage> {
+    let (reply, receiver) = oneshot::channel();
+    render_pool().submit(RenderJob {
+        pdf_path: pdf_path.to_string(),
+        page_index,
+        rotation,
+        scale: 0.0, // プレビューは幅を固定してレンダリングするため使用しない
+        generation,
+        target: RenderTarget::LinkPreview(x, y, reply),
+    });
+
+    receiver
+        .await
+        .map_err(|_| anyhow::anyhow!("ページ {} のプレビューのレンダリングがキャンセルされました", page_index + 1))?
+}
+
+// 検索用に全ページのテキストをワーカープールで抽出（UIスレッドをブロックしない）
+pub async fn extract_document_text_in_background(pdf_path: &str, generation: u64) -> Result<Vec<Vec<TextElement>>> {
+    let (reply, receiver) = oneshot::channel();
+    render_pool().submit(RenderJob {
+        pdf_path: pdf_path.to_string(),
+        page_index: 0,
+        rotation: RotationAngle::None,
+        scale: 0.0,
+        generation,
+        target: RenderTarget::DocumentText(reply),
+    });
+
+    receiver
+        .await
+        .map_err(|_| anyhow::anyhow!("テキストの抽出がキャンセルされました"))?
+}
+
+// 表示中のページを通知し、優先度の再計算と不要になった要求のキャンセルを行う
+pub fn set_render_focus(generation: u64, focus_page: usize, window: Range<usize>) {
+    render_pool().set_focus(generation, focus_page, window);
+}
+
+// PDFiumの所有スレッドが保持しているドキュメントのハンドルを解放し、未処理の要求を破棄
+pub fn release_pdf_document(pdf_path: &str) {
+    close_pdf_document(pdf_path);
+    if let Some(pool) = RENDER_POOL.get() {
+        pool.close_document(pdf_path);
+    }
+}
@@ -0,0 +1,380 @@
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+use crate::types::{IndexPosting, IndexedDocument, LibraryHit, LibraryIndex, TextElement, ViewerSettings};
+use crate::storage::config::ensure_data_dir;
+use crate::storage::load_recent_files;
+
+// 監視フォルダを走査する深さとファイル数の上限
+const MAX_FOLDER_DEPTH: usize = 8;
+const MAX_FOLDER_FILES: usize = 2000;
+
+// 索引の版（CJKの1文字も索引するようにしたため2）
+const LIBRARY_INDEX_VERSION: u32 = 2;
+
+fn empty_index() -> LibraryIndex {
+    LibraryIndex {
+        version: LIBRARY_INDEX_VERSION,
+        ..LibraryIndex::default()
+    }
+}
+
+pub fn load_library_index() -> LibraryIndex {
+    let data_dir = match ensure_data_dir() {
+        Ok(dir) => dir,
+        Err(_) => return empty_index(),
+    };
+
+    let index_path = data_dir.join("library_index.json");
+
+    // 古い版の索引は全ての文書を索引し直す
+    std::fs::read_to_string(&index_path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<LibraryIndex>(&content).ok())
+        .filter(|index| index.version == LIBRARY_INDEX_VERSION)
+        .unwrap_or_else(empty_index)
+}
+
+pub fn save_library_index(index: &LibraryIndex) -> Result<()> {
+    let data_dir = ensure_data_dir()?;
+    let index_path = data_dir.join("library_index.json");
+
+    // 大きくなりやすいため整形せずに保存
+    let json = serde_json::to_string(index)?;
+    std::fs::write(&index_path, json)?;
+
+    Ok(())
+}
+
+// インデックスの対象となる文書（パス, 表示名）：最近開いたファイルと監視フォルダ内のPDF
+pub fn library_sources(settings: &ViewerSettings) -> Vec<(String, String)> {
+    let mut sources: Vec<(String, String)> = load_recent_files()
+        .into_iter()
+        .filter(|file| Path::new(&file.path).exists())
+        .map(|file| (file.path, file.display_name))
+        .collect();
+
+    if let Some(folder) = &settings.library_folder {
+        let mut files = Vec::new();
+        collect_pdf_files(Path::new(folder), 0, &mut files);
+        for path in files {
+            let path_str = path.to_string_lossy().to_string();
+            if sources.iter().any(|(source, _)| *source == path_str) {
+                continue;
+            }
+            let file_name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+            sources.push((path_str, file_name));
+        }
+    }
+
+    sources
+}
+
+fn collect_pdf_files(dir: &Path, depth: usize, files: &mut Vec<std::path::PathBuf>) {
+    if depth > MAX_FOLDER_DEPTH {
+        return;
+    }
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+
+    for entry in entries.flatten() {
+        if files.len() >= MAX_FOLDER_FILES {
+            return;
+        }
+        let path = entry.path();
+        if path.is_dir() {
+            collect_pdf_files(&path, depth + 1, files);
+        } else if path.extension().map_or(false, |ext| ext.eq_ignore_ascii_case("pdf")) {
+            files.push(path);
+        }
+    }
+}
+
+// ファイルサイズと更新日時（変更の検出用）
+fn file_stamp(path: &str) -> Option<(u64, u64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?.as_secs();
+    Some((metadata.len(), modified))
+}
+
+// CJKの文字は単語の区切りがないため、2文字ずつの組（bigram）で索引する
+// 1文字だけの検索語にも一致するよう、索引には1文字ずつの語も加える
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}' // ひらがな・カタカナ
+        | '\u{3400}'..='\u{4DBF}' | '\u{4E00}'..='\u{9FFF}' | '\u{F900}'..='\u{FAFF}' // 漢字
+        | '\u{AC00}'..='\u{D7AF}' // ハングル
+        | '\u{FF66}'..='\u{FF9F}' // 半角カタカナ
+    )
+}
+
+fn tokenize(text: &str, include_unigrams: bool) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    let mut cjk_run = Vec::<char>::new();
+
+    let flush_word = |word: &mut String, tokens: &mut Vec<String>| {
+        if !word.is_empty() {
+            tokens.push(std::mem::take(word));
+        }
+    };
+    let flush_cjk = |cjk_run: &mut Vec<char>, tokens: &mut Vec<String>| {
+        match cjk_run.len() {
+            0 => {}
+            1 => tokens.push(cjk_run[0].to_string()),
+            _ => {
+                tokens.extend(cjk_run.windows(2).map(|pair| pair.iter().collect::<String>()));
+                if include_unigrams {
+                    tokens.extend(cjk_run.iter().map(|c| c.to_string()));
+                }
+            }
+        }
+        cjk_run.clear();
+    };
+
+    for c in text.chars().flat_map(char::to_lowercase) {
+        if is_cjk(c) {
+            flush_word(&mut word, &mut tokens);
+            cjk_run.push(c);
+        } else if c.is_alphanumeric() {
+            flush_cjk(&mut cjk_run, &mut tokens);
+            word.push(c);
+        } else {
+            flush_word(&mut word, &mut tokens);
+            flush_cjk(&mut cjk_run, &mut tokens);
+        }
+    }
+    flush_word(&mut word, &mut tokens);
+    flush_cjk(&mut cjk_run, &mut tokens);
+
+    tokens
+}
+
+impl LibraryIndex {
+    // 未登録か、前回の索引作成後に変更された文書
+    pub fn stale_documents(&self, sources: &[(String, String)]) -> Vec<(String, String)> {
+        sources
+            .iter()
+            .filter(|(path, _)| {
+                let indexed = self.documents.iter().find(|document| document.path == *path);
+                match (indexed, file_stamp(path)) {
+                    (Some(document), Some(stamp)) => (document.file_size, document.modified) != stamp,
+                    (None, Some(_)) => true,
+                    (_, None) => false,
+                }
+            })
+            .cloned()
+            .collect()
+    }
+
+    // 対象から外れた文書や削除されたファイルを取り除く（変更があった場合はtrue）
+    pub fn remove_missing(&mut self, sources: &[(String, String)]) -> bool {
+        let source_paths: HashSet<&str> = sources.iter().map(|(path, _)| path.as_str()).collect();
+        let removed: Vec<String> = self.documents
+            .iter()
+            .filter(|document| !source_paths.contains(document.path.as_str()) || !Path::new(&document.path).exists())
+            .map(|document| document.path.clone())
+            .collect();
+
+        for path in &removed {
+            self.remove_document(path);
+        }
+        !removed.is_empty()
+    }
+
+    pub fn remove_document(&mut self, path: &str) {
+        let Some(position) = self.documents.iter().position(|document| document.path == path) else { return };
+        let document_id = self.documents.remove(position).id;
+
+        for postings in self.postings.values_mut() {
+            postings.retain(|posting| posting.document_id != document_id);
+        }
+        self.postings.retain(|_, postings| !postings.is_empty());
+    }
+
+    // 文書のテキストを索引に登録（既に登録されている場合は置き換える）
+    pub fn add_document(&mut self, path: &str, display_name: &str, pages: &[Vec<TextElement>]) {
+        self.remove_document(path);
+        let Some((file_size, modified)) = file_stamp(path) else { return };
+
+        let document_id = self.next_document_id;
+        self.next_document_id += 1;
+
+        for (page_index, elements) in pages.iter().enumerate() {
+            let text = elements.iter().map(|element| element.text.as_str()).collect::<Vec<_>>().join(" ");
+            let mut counts = HashMap::<String, u32>::new();
+            for token in tokenize(&text, true) {
+                *counts.entry(token).or_insert(0) += 1;
+            }
+            for (term, count) in counts {
+                self.postings.entry(term).or_default().push(IndexPosting {
+                    document_id,
+                    page_index: page_index as u32,
+                    count,
+                });
+            }
+        }
+
+        self.documents.push(IndexedDocument {
+            id: document_id,
+            path: path.to_string(),
+            display_name: display_name.to_string(),
+            file_size,
+            modified,
+            page_count: pages.len(),
+        });
+    }
+
+    // 全ての語を含むページをTF-IDFの順に返す
+    pub fn search(&self, query: &str, limit: usize) -> Vec<LibraryHit> {
+        let mut terms = tokenize(query, false);
+        terms.sort();
+        terms.dedup();
+        if terms.is_empty() {
+            return Vec::new();
+        }
+
+        let total_pages = self.documents.iter().map(|document| document.page_count).sum::<usize>().max(1) as f32;
+        let mut scores = HashMap::<(u32, u32), (f32, usize)>::new();
+        for term in &terms {
+            let Some(postings) = self.postings.get(term) else {
+                return Vec::new();
+            };
+            let idf = (1.0 + total_pages / postings.len() as f32).ln();
+            for posting in postings {
+                let entry = scores.entry((posting.document_id, posting.page_index)).or_insert((0.0, 0));
+                entry.0 += (1.0 + (posting.count as f32).ln()) * idf;
+                entry.1 += 1;
+            }
+        }
+
+        let documents: HashMap<u32, &IndexedDocument> = self.documents.iter().map(|document| (document.id, document)).collect();
+        let mut hits: Vec<LibraryHit> = scores
+            .into_iter()
+            .filter(|(_, (_, matched))| *matched == terms.len())
+            .filter_map(|((document_id, page_index), (score, _))| {
+                let document = documents.get(&document_id)?;
+                Some(LibraryHit {
+                    path: document.path.clone(),
+                    display_name: document.display_name.clone(),
+                    page_index: page_index as usize,
+                    score,
+                })
+            })
+            .collect();
+
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.path.cmp(&b.path)).then(a.page_index.cmp(&b.page_index)));
+        hits.truncate(limit);
+        hits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TextBounds;
+
+    fn page(text: &str) -> Vec<TextElement> {
+        text.split_whitespace()
+            .map(|word| TextElement {
+                text: word.to_string(),
+                bounds: TextBounds { x: 0.0, y: 0.0, width: 10.0, height: 10.0 },
+                font_size: 10.0,
+                vertical: false,
+            })
+            .collect()
+    }
+
+    // add_documentはファイルの更新日時を記録するため、実在するファイルが必要
+    fn temp_file(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("library_index_test_{}_{}.pdf", std::process::id(), name));
+        std::fs::write(&path, name).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn tokenizes_words_and_cjk_bigrams() {
+        assert_eq!(tokenize("Hello, PDF-Viewer 2", false), vec!["hello", "pdf", "viewer", "2"]);
+        assert_eq!(tokenize("世界です", false), vec!["世界", "界で", "です"]);
+        assert_eq!(tokenize("PDF版の説明", false), vec!["pdf", "版の", "の説", "説明"]);
+        assert_eq!(tokenize("本", false), vec!["本"]);
+    }
+
+    #[test]
+    fn index_tokens_include_cjk_unigrams() {
+        assert_eq!(tokenize("日本", true), vec!["日本", "日", "本"]);
+        assert_eq!(tokenize("本 abc", true), vec!["本", "abc"]);
+    }
+
+    #[test]
+    fn single_cjk_character_matches_inside_longer_words() {
+        let path = temp_file("unigram");
+        let mut index = empty_index();
+        index.add_document(&path, "unigram", &[page("日本語の文書"), page("English only")]);
+
+        let hits = index.search("語", 10);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].page_index, 0);
+        assert_eq!(index.search("日本語", 10).len(), 1);
+        assert!(index.search("中", 10).is_empty());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn requires_every_query_term() {
+        let path = temp_file("terms");
+        let mut index = empty_index();
+        index.add_document(&path, "terms", &[page("rust pdf viewer"), page("rust compiler")]);
+
+        let hits = index.search("rust pdf", 10);
+        assert_eq!(hits.iter().map(|hit| hit.page_index).collect::<Vec<_>>(), vec![0]);
+        assert!(index.search("rust python", 10).is_empty());
+        assert!(index.search("   ", 10).is_empty());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn ranks_frequent_and_rare_terms_higher() {
+        let first = temp_file("rank_first");
+        let second = temp_file("rank_second");
+        let mut index = empty_index();
+        index.add_document(&first, "first", &[page("index index index"), page("index"), page("common")]);
+        index.add_document(&second, "second", &[page("common"), page("common rare")]);
+
+        // 出現回数が多いページが先に来る
+        let hits = index.search("index", 10);
+        assert_eq!(hits.iter().map(|hit| hit.page_index).collect::<Vec<_>>(), vec![0, 1]);
+        assert!(hits[0].score > hits[1].score);
+
+        // 多くのページに現れる語より、まれな語の方が重みが大きい
+        let rare = index.search("rare", 10);
+        let common = index.search("common", 10);
+        assert_eq!(common.len(), 3);
+        assert!(rare[0].score > common[0].score);
+
+        assert_eq!(index.search("rare", 10)[0].display_name, "second");
+        assert_eq!(index.search("common", 2).len(), 2);
+
+        let _ = std::fs::remove_file(&first);
+        let _ = std::fs::remove_file(&second);
+    }
+
+    #[test]
+    fn replacing_a_document_drops_its_old_postings() {
+        let path = temp_file("replace");
+        let mut index = empty_index();
+        index.add_document(&path, "replace", &[page("before")]);
+        index.add_document(&path, "replace", &[page("after")]);
+
+        assert!(index.search("before", 10).is_empty());
+        assert_eq!(index.search("after", 10).len(), 1);
+        assert_eq!(index.documents.len(), 1);
+
+        index.remove_document(&path);
+        assert!(index.postings.is_empty());
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -1,35 +1,68 @@
 use anyhow::Result;
-use crate::types::{PositionMarker, PdfMarkers};
+use crate::types::{PositionMarker, PdfMarkers, RotationAngle};
 use crate::storage::config::ensure_data_dir;
+use crate::storage::load_page_rotations;
 
-pub fn load_position_markers(pdf_path: &str) -> Vec<PositionMarker> {
+// 現在のマーカーの保存形式（座標は回転前のページ上の相対位置）
+const MARKERS_FORMAT_VERSION: u32 = 1;
+
+// 全PDFのマーカーを読み込む（古い形式のものは変換して保存し直す）
+fn load_all_markers() -> Vec<PdfMarkers> {
     let data_dir = match ensure_data_dir() {
         Ok(dir) => dir,
         Err(_) => return Vec::new(),
     };
-    
+
     let markers_path = data_dir.join("position_markers.json");
-    
-    if let Ok(content) = std::fs::read_to_string(&markers_path) {
-        let all_markers: Vec<PdfMarkers> = serde_json::from_str(&content).unwrap_or_default();
-        if let Some(pdf_markers) = all_markers.iter().find(|m| m.pdf_path == pdf_path) {
-            return pdf_markers.markers.clone();
+
+    let mut all_markers: Vec<PdfMarkers> = if let Ok(content) = std::fs::read_to_string(&markers_path) {
+        serde_json::from_str(&content).unwrap_or_default()
+    } else {
+        return Vec::new();
+    };
+
+    // 以前は表示上（回転後）の座標で保存していたため、現在の回転状態で置かれたものとみなして回転前の座標に戻す
+    let mut migrated = false;
+    for pdf_markers in all_markers.iter_mut().filter(|m| m.format_version < MARKERS_FORMAT_VERSION) {
+        let rotations = load_page_rotations(&pdf_markers.pdf_path);
+        for marker in pdf_markers.markers.iter_mut() {
+            let rotation = rotations.get(&marker.page_index).copied().unwrap_or(RotationAngle::None);
+            (marker.x, marker.y) = rotation.unrotate_fraction(marker.x, marker.y);
+        }
+        pdf_markers.format_version = MARKERS_FORMAT_VERSION;
+        migrated = true;
+    }
+
+    if migrated {
+        if let Err(e) = save_all_markers(&all_markers) {
+            println!("マーカーの変換結果を保存できませんでした: {}", e);
         }
     }
-    
-    Vec::new()
+
+    all_markers
 }
 
-pub fn save_position_marker(pdf_path: &str, marker: PositionMarker) -> Result<()> {
+fn save_all_markers(all_markers: &[PdfMarkers]) -> Result<()> {
     let data_dir = ensure_data_dir()?;
     let markers_path = data_dir.join("position_markers.json");
-    
-    let mut all_markers: Vec<PdfMarkers> = if let Ok(content) = std::fs::read_to_string(&markers_path) {
-        serde_json::from_str(&content).unwrap_or_default()
-    } else {
-        Vec::new()
-    };
-    
+
+    let json = serde_json::to_string_pretty(all_markers)?;
+    std::fs::write(&markers_path, json)?;
+
+    Ok(())
+}
+
+pub fn load_position_markers(pdf_path: &str) -> Vec<PositionMarker> {
+    load_all_markers()
+        .into_iter()
+        .find(|m| m.pdf_path == pdf_path)
+        .map(|pdf_markers| pdf_markers.markers)
+        .unwrap_or_default()
+}
+
+pub fn save_position_marker(pdf_path: &str, marker: PositionMarker) -> Result<()> {
+    let mut all_markers = load_all_markers();
+
     // このPDFのマーカーセットを見つけるか作成
     if let Some(existing) = all_markers.iter_mut().find(|m| m.pdf_path == pdf_path) {
         existing.markers.push(marker);
@@ -39,34 +72,25 @@ pub fn save_position_marker(pdf_path: &str, marker: PositionMarker) -> Result<()
             pdf_path: pdf_path.to_string(),
             markers: vec![marker],
             last_modified: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+            format_version: MARKERS_FORMAT_VERSION,
         };
         all_markers.push(pdf_markers);
     }
-    
-    let json = serde_json::to_string_pretty(&all_markers)?;
-    std::fs::write(&markers_path, json)?;
-    
-    Ok(())
+
+    save_all_markers(&all_markers)
 }
 
 pub fn delete_position_marker(pdf_path: &str, marker_id: &str) -> Result<()> {
-    let data_dir = ensure_data_dir()?;
-    let markers_path = data_dir.join("position_markers.json");
-    
-    let mut all_markers: Vec<PdfMarkers> = if let Ok(content) = std::fs::read_to_string(&markers_path) {
-        serde_json::from_str(&content).unwrap_or_default()
-    } else {
+    let mut all_markers = load_all_markers();
+    if all_markers.is_empty() {
         return Ok(()); // ファイルが存在しない場合は何もしない
-    };
-    
+    }
+
     // 指定されたマーカーを削除
     if let Some(existing) = all_markers.iter_mut().find(|m| m.pdf_path == pdf_path) {
         existing.markers.retain(|marker| marker.id != marker_id);
         existing.last_modified = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string();
     }
-    
-    let json = serde_json::to_string_pretty(&all_markers)?;
-    std::fs::write(&markers_path, json)?;
-    
-    Ok(())
-}
\ No newline at end of file
+
+    save_all_markers(&all_markers)
+}
@@ -5,6 +5,9 @@ pub mod recent_files;
 pub mod markers;
 pub mod rotations;
 pub mod api_keys;
+pub mod settings;
+pub mod render_cache;
+pub mod library_index;
 
 // pub  // Currently unused
 pub use flashcards::*;
@@ -12,4 +15,7 @@ pub use bookmarks::*;
 pub use recent_files::*;
 pub use markers::*;
 pub use rotations::*;
-pub use api_keys::*;
\ No newline at end of file
+pub use api_keys::*;
+pub use settings::*;
+pub use render_cache::*;
+pub use library_index::*;
\ No newline at end of file
@@ -30,6 +30,13 @@ pub fn save_recent_files(recent_files: &[RecentFile]) -> Result<()> {
 pub fn add_recent_file(file_path: String, file_name: String) -> Result<()> {
     let mut recent_files = load_recent_files();
     
+    // 以前に開いたときの表示名（文書のタイトル）を引き継ぐ
+    let display_name = recent_files
+        .iter()
+        .find(|file| file.path == file_path)
+        .map(|file| file.display_name.clone())
+        .unwrap_or_else(|| file_name.clone());
+    
     // 既存のファイルを削除（重複を避けるため）
     recent_files.retain(|file| file.path != file_path);
     
@@ -37,7 +44,7 @@ pub fn add_recent_file(file_path: String, file_name: String) -> Result<()> {
     let recent_file = RecentFile {
         path: file_path.clone(),
         name: file_name.clone(),
-        display_name: file_name,
+        display_name,
         last_opened: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string(),
     };
     
@@ -48,4 +55,22 @@ pub fn add_recent_file(file_path: String, file_name: String) -> Result<()> {
     
     save_recent_files(&recent_files)?;
     Ok(())
+}
+
+// 表示名を文書のタイトルに更新（タイトルが設定されていない場合はファイル名）
+// 表示名が変わった場合はtrueを返す
+pub fn set_recent_file_title(file_path: &str, title: Option<String>) -> Result<bool> {
+    let mut recent_files = load_recent_files();
+    let Some(recent_file) = recent_files.iter_mut().find(|file| file.path == file_path) else {
+        return Ok(false);
+    };
+    
+    let display_name = title.unwrap_or_else(|| recent_file.name.clone());
+    if recent_file.display_name == display_name {
+        return Ok(false);
+    }
+    recent_file.display_name = display_name;
+    
+    save_recent_files(&recent_files)?;
+    Ok(true)
 }
\ No newline at end of file
@@ -0,0 +1,206 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+use crate::types::{CachedPageRender, PdfPageData, RotationAngle, TileKey};
+use crate::pdf::PageImage;
+use crate::storage::config::ensure_data_dir;
+use crate::storage::load_viewer_settings;
+
+// この回数だけ保存するごとに、上限を超えたキャッシュを削除する
+const CLEANUP_INTERVAL: usize = 32;
+
+// ページのテキストやリンクの抽出・座標の扱いを変更したら更新する
+const CACHE_FORMAT_VERSION: u32 = 4;
+
+static SAVE_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+// パス -> (ファイルサイズ, 更新日時, 内容のハッシュ)
+// 同じファイルを何度も読み込んでハッシュを計算しないようにする
+static CONTENT_HASHES: OnceLock<Mutex<HashMap<String, (u64, SystemTime, String)>>> = OnceLock::new();
+
+fn render_cache_dir() -> std::io::Result<PathBuf> {
+    let cache_dir = ensure_data_dir()?.join("render_cache");
+    std::fs::create_dir_all(&cache_dir)?;
+    Ok(cache_dir)
+}
+
+// ファイル内容のハッシュ（FNV-1a 64bit）
+// ファイルを移動・リネームしてもキャッシュを再利用でき、内容が変わった場合は別のキャッシュになる
+pub fn document_content_hash(pdf_path: &str) -> Result<String> {
+    let metadata = std::fs::metadata(pdf_path)?;
+    let file_size = metadata.len();
+    let modified = metadata.modified()?;
+
+    let hashes = CONTENT_HASHES.get_or_init(|| Mutex::new(HashMap::new()));
+    if let Ok(hashes) = hashes.lock() {
+        if let Some((size, time, hash)) = hashes.get(pdf_path) {
+            if *size == file_size && *time == modified {
+                return Ok(hash.clone());
+            }
+        }
+    }
+
+    let mut file = std::io::BufReader::new(std::fs::File::open(pdf_path)?);
+    let mut buffer = vec![0u8; 1024 * 1024];
+    let mut hash: u64 = 0xcbf29ce484222325;
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        for byte in &buffer[..read] {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    let hash = format!("{:016x}-{:x}", hash, file_size);
+
+    if let Ok(mut hashes) = hashes.lock() {
+        hashes.insert(pdf_path.to_string(), (file_size, modified, hash.clone()));
+    }
+
+    Ok(hash)
+}
+
+fn cached_page_paths(pdf_path: &str, page_index: usize, rotation: RotationAngle, scale: f32) -> Result<(PathBuf, PathBuf)> {
+    let document_dir = render_cache_dir()?.join(document_content_hash(pdf_path)?);
+    let file_stem = format!("p{}_r{}_s{}", page_index, rotation as u8, TileKey::scale_key(scale));
+    Ok((
+        document_dir.join(format!("{}.png", file_stem)),
+        document_dir.join(format!("{}.json", file_stem)),
+    ))
+}
+
+fn cached_thumbnail_path(pdf_path: &str, page_index: usize, rotation: RotationAngle) -> Result<PathBuf> {
+    let document_dir = render_cache_dir()?.join(document_content_hash(pdf_path)?);
+    Ok(document_dir.join(format!("t{}_r{}.png", page_index, rotation as u8)))
+}
+
+// 最近使ったキャッシュとして更新日時を新しくする（削除の優先順位に使う）
+fn touch_cache_file(path: &Path) {
+    if let Ok(file) = std::fs::File::options().append(true).open(path) {
+        let _ = file.set_modified(SystemTime::now());
+    }
+}
+
+// ディスクキャッシュからレンダリング済みページを読み込む（見つからない場合はNone）
+pub fn load_cached_page(pdf_path: &str, page_index: usize, rotation: RotationAngle, scale: f32) -> Option<PdfPageData> {
+    let (image_path, text_path) = cached_page_paths(pdf_path, page_index, rotation, scale).ok()?;
+
+    let content = std::fs::read_to_string(&text_path).ok()?;
+    let cached: CachedPageRender = serde_json::from_str(&content).ok()?;
+    if cached.format_version != CACHE_FORMAT_VERSION {
+        return None;
+    }
+    let png_data = std::fs::read(&image_path).ok()?;
+
+    touch_cache_file(&image_path);
+    touch_cache_file(&text_path);
+
+    Some(PdfPageData {
+        image_data: PageImage::new(png_data),
+        text_elements: cached.text_elements,
+        page_width: cached.page_width,
+        page_height: cached.page_height,
+        page_index,
+        rotation,
+        render_scale: scale,
+        links: cached.links,
+    })
+}
+
+pub fn save_cached_page(pdf_path: &str, page_data: &PdfPageData) -> Result<()> {
+    let Some(png_data) = page_data.image_data.bytes() else {
+        return Ok(());
+    };
+
+    let (image_path, text_path) = cached_page_paths(pdf_path, page_data.page_index, page_data.rotation, page_data.render_scale)?;
+    if let Some(document_dir) = image_path.parent() {
+        std::fs::create_dir_all(document_dir)?;
+    }
+
+    let cached = CachedPageRender {
+        page_width: page_data.page_width,
+        page_height: page_data.page_height,
+        text_elements: page_data.text_elements.clone(),
+        links: page_data.links.clone(),
+        format_version: CACHE_FORMAT_VERSION,
+    };
+    std::fs::write(&image_path, png_data.as_slice())?;
+    std::fs::write(&text_path, serde_json::to_string(&cached)?)?;
+
+    cleanup_periodically()
+}
+
+pub fn load_cached_thumbnail(pdf_path: &str, page_index: usize, rotation: RotationAngle) -> Option<PageImage> {
+    let thumbnail_path = cached_thumbnail_path(pdf_path, page_index, rotation).ok()?;
+    let png_data = std::fs::read(&thumbnail_path).ok()?;
+    touch_cache_file(&thumbnail_path);
+    Some(PageImage::new(png_data))
+}
+
+pub fn save_cached_thumbnail(pdf_path: &str, page_index: usize, rotation: RotationAngle, thumbnail: &PageImage) -> Result<()> {
+    let Some(png_data) = thumbnail.bytes() else {
+        return Ok(());
+    };
+
+    let thumbnail_path = cached_thumbnail_path(pdf_path, page_index, rotation)?;
+    if let Some(document_dir) = thumbnail_path.parent() {
+        std::fs::create_dir_all(document_dir)?;
+    }
+    std::fs::write(&thumbnail_path, png_data.as_slice())?;
+
+    cleanup_periodically()
+}
+
+fn cleanup_periodically() -> Result<()> {
+    if SAVE_COUNT.fetch_add(1, Ordering::Relaxed) % CLEANUP_INTERVAL == 0 {
+        cleanup_render_cache()?;
+    }
+    Ok(())
+}
+
+// キャッシュ全体が上限を超えている場合、最も長く使われていないファイルから削除
+pub fn cleanup_render_cache() -> Result<()> {
+    let limit_bytes = load_viewer_settings().render_cache_limit_mb * 1024 * 1024;
+    let cache_dir = render_cache_dir()?;
+
+    let mut files = Vec::new();
+    let mut total_size = 0u64;
+    for document_dir in std::fs::read_dir(&cache_dir)?.flatten() {
+        let Ok(entries) = std::fs::read_dir(document_dir.path()) else { continue };
+        for entry in entries.flatten() {
+            let Ok(metadata) = entry.metadata() else { continue };
+            if !metadata.is_file() {
+                continue;
+            }
+            total_size += metadata.len();
+            files.push((metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH), metadata.len(), entry.path()));
+        }
+    }
+
+    if total_size <= limit_bytes {
+        return Ok(());
+    }
+
+    files.sort_by_key(|(modified, _, _)| *modified);
+    for (_, size, path) in files {
+        if total_size <= limit_bytes {
+            break;
+        }
+        if std::fs::remove_file(&path).is_ok() {
+            total_size -= size;
+        }
+    }
+
+    // 空になったドキュメントのディレクトリを削除
+    for document_dir in std::fs::read_dir(&cache_dir)?.flatten() {
+        let _ = std::fs::remove_dir(document_dir.path());
+    }
+
+    Ok(())
+}
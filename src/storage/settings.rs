@@ -0,0 +1,44 @@
+use anyhow::Result;
+use crate::types::{ViewerSettings, default_render_cache_limit_mb};
+use crate::storage::config::ensure_data_dir;
+
+pub fn load_viewer_settings() -> ViewerSettings {
+    let data_dir = match ensure_data_dir() {
+        Ok(dir) => dir,
+        Err(_) => return ViewerSettings::default(),
+    };
+    
+    let settings_path = data_dir.join("settings.json");
+    
+    if let Ok(content) = std::fs::read_to_string(&settings_path) {
+        serde_json::from_str(&content).unwrap_or_default()
+    } else {
+        ViewerSettings::default()
+    }
+}
+
+pub fn save_viewer_settings(settings: &ViewerSettings) -> Result<()> {
+    let data_dir = ensure_data_dir()?;
+    let settings_path = data_dir.join("settings.json");
+    
+    let json = serde_json::to_string_pretty(settings)?;
+    std::fs::write(&settings_path, json)?;
+    
+    Ok(())
+}
+
+impl ViewerSettings {
+    pub fn page_cache_budget_bytes(&self) -> usize {
+        self.page_cache_budget_mb * 1024 * 1024
+    }
+}
+
+impl Default for ViewerSettings {
+    fn default() -> Self {
+        Self {
+            page_cache_budget_mb: 256,
+            render_cache_limit_mb: default_render_cache_limit_mb(),
+            library_folder: None,
+        }
+    }
+}
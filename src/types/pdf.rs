@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
+use crate::pdf::PageImage;
 
-#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum RotationAngle {
     None = 0,      // 0度
     Rotate90 = 1,  // 90度（時計回り）
@@ -18,7 +19,6 @@ impl RotationAngle {
         }
     }
     
-    #[allow(dead_code)]
     pub fn to_degrees(self) -> f32 {
         match self {
             RotationAngle::None => 0.0,
@@ -27,29 +27,364 @@ impl RotationAngle {
             RotationAngle::Rotate270 => 270.0,
         }
     }
+    
+    // 回転前のページ上の相対位置（0.0-1.0）を、時計回りに回転して表示したページ上の相対位置に変換
+    pub fn rotate_fraction(self, x: f32, y: f32) -> (f32, f32) {
+        match self {
+            RotationAngle::None => (x, y),
+            RotationAngle::Rotate90 => (1.0 - y, x),
+            RotationAngle::Rotate180 => (1.0 - x, 1.0 - y),
+            RotationAngle::Rotate270 => (y, 1.0 - x),
+        }
+    }
+    
+    // 表示上の相対位置を、回転前のページ上の相対位置に戻す
+    pub fn unrotate_fraction(self, x: f32, y: f32) -> (f32, f32) {
+        match self {
+            RotationAngle::None => (x, y),
+            RotationAngle::Rotate90 => (y, 1.0 - x),
+            RotationAngle::Rotate180 => (1.0 - x, 1.0 - y),
+            RotationAngle::Rotate270 => (1.0 - y, x),
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct PdfPageData {
-    pub image_data: String,
+    pub image_data: PageImage, // カスタムスキームで配信するレンダリング済み画像
     pub text_elements: Vec<TextElement>,
     pub page_width: f32,
     pub page_height: f32,
     pub page_index: usize, // 混入チェック用
     pub rotation: RotationAngle, // ページの回転状態
+    pub render_scale: f32, // レンダリング倍率（1ポイントあたりのピクセル数）
+    pub links: Vec<PdfLink>, // リンク注釈
+}
+
+impl PdfPageData {
+    // 回転前のページ座標の矩形を、このページの表示上の矩形に変換
+    pub fn rotate_bounds(&self, bounds: &TextBounds) -> TextBounds {
+        let (width, height) = match self.rotation {
+            RotationAngle::Rotate90 | RotationAngle::Rotate270 => (self.page_height, self.page_width),
+            _ => (self.page_width, self.page_height),
+        };
+        bounds.rotated(self.rotation, width, height)
+    }
+}
+
+// リンクの移動先
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum LinkTarget {
+    // 文書内のページ（x, yはページ左上からの位置、回転前のポイント単位）
+    Page {
+        page_index: usize,
+        y: Option<f32>,
+        #[serde(default)]
+        x: Option<f32>,
+    },
+    Uri(String),                                // 外部のURI
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PdfLink {
+    pub bounds: TextBounds,
+    pub target: LinkTarget,
+}
+
+// 文書のプロパティ（メタデータが設定されていない項目はNone）
+#[derive(Clone, Debug, PartialEq)]
+pub struct DocumentInfo {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub subject: Option<String>,
+    pub keywords: Option<String>,
+    pub creator: Option<String>,
+    pub producer: Option<String>,
+    pub creation_date: Option<String>,
+    pub modification_date: Option<String>,
+    pub pdf_version: String,
+    pub page_count: usize,
+    pub file_size: u64, // バイト数
+    pub is_encrypted: bool,       // 暗号化されているか
+    pub requires_password: bool,  // 開くためにパスワードが必要だったか
+    pub permissions: DocumentPermissions,
+}
+
+// 文書に設定されている操作の許可
+#[derive(Clone, Debug, PartialEq)]
+pub struct DocumentPermissions {
+    pub can_print: bool,
+    pub can_copy: bool,
+    pub can_modify: bool,
+    pub can_annotate: bool,
+}
+
+// 文書を開けなかった理由
+#[derive(Clone, Debug, PartialEq)]
+pub enum DocumentLoadError {
+    NotSelected,
+    Loading,
+    PasswordRequired,
+    Failed,
+}
+
+impl DocumentLoadError {
+    pub fn message(&self) -> &'static str {
+        match self {
+            DocumentLoadError::NotSelected => "PDFファイルが選択されていません",
+            DocumentLoadError::Loading => "読み込み中…",
+            DocumentLoadError::PasswordRequired => "このPDFはパスワードで保護されています",
+            DocumentLoadError::Failed => "PDFの読み込みに失敗しました",
+        }
+    }
+}
+
+// 文書のアウトライン（目次）の項目
+#[derive(Clone, Debug, PartialEq)]
+pub struct OutlineItem {
+    pub title: String,
+    pub page_index: Option<usize>, // 移動先のページ（移動先がない項目はNone）
+    pub children: Vec<OutlineItem>,
+}
+
+// 文書内検索のオプション
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SearchOptions {
+    pub case_sensitive: bool, // 大文字と小文字を区別する
+    pub whole_word: bool,     // 単語単位で一致させる
+    pub use_regex: bool,      // 正規表現として扱う
+}
+
+// 文書内検索の一致箇所
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchMatch {
+    pub page_index: usize,
+    pub rects: Vec<TextBounds>, // 強調表示する矩形（左上原点のページ座標）
+    pub snippet: String,        // 結果一覧に表示する前後の文脈
+}
+
+// ページ画像内の矩形領域（回転後・レンダリング倍率適用後のピクセル座標）
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TileRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+// タイルキャッシュのキー（倍率は1/16刻みに丸められているため16倍した整数で保持）
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TileKey {
+    pub page_index: usize,
+    pub rotation: RotationAngle,
+    pub scale_key: u32,
+    pub region: TileRegion,
+}
+
+impl TileKey {
+    pub fn new(page_index: usize, rotation: RotationAngle, scale: f32, region: TileRegion) -> Self {
+        Self {
+            page_index,
+            rotation,
+            scale_key: Self::scale_key(scale),
+            region,
+        }
+    }
+    
+    pub fn scale_key(scale: f32) -> u32 {
+        (scale * 16.0).round() as u32
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
+pub struct PdfTileData {
+    pub image_data: PageImage,
+    pub key: TileKey,
+    pub full_width: u32,  // ページ全体をこの倍率でレンダリングした場合の幅（ピクセル）
+    pub full_height: u32, // 同じく高さ（ピクセル）
+}
+
+// ページ整理で並べるページの出どころ
+#[derive(Clone, Debug, PartialEq)]
+pub enum OrganizerPageSource {
+    Original(usize),                           // 開いている文書のページ
+    Imported { path: String, page_index: usize }, // 他のPDFから取り込んだページ
+    Blank,                                     // 挿入した空白ページ
+}
+
+// ページ整理で編集中のページ
+#[derive(Clone, Debug, PartialEq)]
+pub struct OrganizerPage {
+    pub id: u64, // 複製したページも区別するための一意なID
+    pub source: OrganizerPageSource,
+    pub rotation: RotationAngle,
+    pub width: f32,  // 回転前の幅（ポイント）
+    pub height: f32, // 回転前の高さ（ポイント）
+}
+
+impl OrganizerPage {
+    // プレビューをレンダリングする元の文書とページ（空白ページはNone）
+    pub fn preview_source(&self, pdf_path: &str) -> Option<(String, usize)> {
+        match &self.source {
+            OrganizerPageSource::Original(page_index) => Some((pdf_path.to_string(), *page_index)),
+            OrganizerPageSource::Imported { path, page_index } => Some((path.clone(), *page_index)),
+            OrganizerPageSource::Blank => None,
+        }
+    }
+    
+    // 回転後の縦横比（幅 / 高さ）
+    pub fn aspect_ratio(&self) -> f32 {
+        match self.rotation {
+            RotationAngle::Rotate90 | RotationAngle::Rotate270 => self.height / self.width,
+            _ => self.width / self.height,
+        }
+    }
+}
+
+// PDFの分割方法
+#[derive(Clone, Debug, PartialEq)]
+pub enum SplitMode {
+    PageRanges(String),  // 「1-3, 5, 8-」のようなページ範囲の指定（1から始まる）
+    EveryPages(usize),   // 指定したページ数ごと
+    TopLevelOutline,     // 最上位のしおりの位置ごと
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct TextElement {
     pub text: String,
     pub bounds: TextBounds,
     pub font_size: f32,
+    #[serde(default)]
+    pub vertical: bool, // 縦書き（上から下へ文字が並ぶ）
 }
 
-#[derive(Clone, Debug, PartialEq)]
+impl TextElement {
+    // テキストレイヤーのCSSのwriting-mode
+    pub fn writing_mode(&self) -> &'static str {
+        if self.vertical { "vertical-rl" } else { "horizontal-tb" }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct TextBounds {
     pub x: f32,
     pub y: f32,
     pub width: f32,
     pub height: f32,
-}
\ No newline at end of file
+}
+
+impl TextBounds {
+    // 回転前のページ（幅・高さ）上の矩形を、時計回りに回転して表示したページ上の矩形に変換
+    pub fn rotated(&self, rotation: RotationAngle, page_width: f32, page_height: f32) -> TextBounds {
+        match rotation {
+            RotationAngle::None => self.clone(),
+            RotationAngle::Rotate90 => TextBounds {
+                x: page_height - (self.y + self.height),
+                y: self.x,
+                width: self.height,
+                height: self.width,
+            },
+            RotationAngle::Rotate180 => TextBounds {
+                x: page_width - (self.x + self.width),
+                y: page_height - (self.y + self.height),
+                width: self.width,
+                height: self.height,
+            },
+            RotationAngle::Rotate270 => TextBounds {
+                x: self.y,
+                y: page_width - (self.x + self.width),
+                width: self.height,
+                height: self.width,
+            },
+        }
+    }
+
+    // 回転した矩形の、回転前の幅と高さ（テキストを回転前の向きで配置してからCSSで回転させる）
+    pub fn unrotated_size(&self, rotation: RotationAngle) -> (f32, f32) {
+        match rotation {
+            RotationAngle::Rotate90 | RotationAngle::Rotate270 => (self.height, self.width),
+            _ => (self.width, self.height),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANGLES: [RotationAngle; 4] = [RotationAngle::None, RotationAngle::Rotate90, RotationAngle::Rotate180, RotationAngle::Rotate270];
+
+    fn assert_close(actual: (f32, f32), expected: (f32, f32)) {
+        assert!(
+            (actual.0 - expected.0).abs() < 1e-5 && (actual.1 - expected.1).abs() < 1e-5,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn unrotate_fraction_reverses_rotate_fraction() {
+        for rotation in ANGLES {
+            for point in [(0.0, 0.0), (1.0, 0.0), (0.25, 0.75), (0.1, 0.9), (0.5, 0.5)] {
+                let (x, y) = rotation.rotate_fraction(point.0, point.1);
+                assert_close(rotation.unrotate_fraction(x, y), point);
+            }
+        }
+    }
+
+    #[test]
+    fn rotate_fraction_moves_top_left_corner_clockwise() {
+        assert_close(RotationAngle::None.rotate_fraction(0.0, 0.0), (0.0, 0.0));
+        assert_close(RotationAngle::Rotate90.rotate_fraction(0.0, 0.0), (1.0, 0.0));
+        assert_close(RotationAngle::Rotate180.rotate_fraction(0.0, 0.0), (1.0, 1.0));
+        assert_close(RotationAngle::Rotate270.rotate_fraction(0.0, 0.0), (0.0, 1.0));
+    }
+
+    #[test]
+    fn rotated_bounds_follow_the_page_corners() {
+        // 横200×縦100のページの左上にある20×10の矩形
+        let bounds = TextBounds { x: 0.0, y: 0.0, width: 20.0, height: 10.0 };
+        assert_eq!(bounds.rotated(RotationAngle::None, 200.0, 100.0), bounds);
+        // 90度: 表示上は横100×縦200になり、右上に来る
+        assert_eq!(bounds.rotated(RotationAngle::Rotate90, 200.0, 100.0), TextBounds { x: 90.0, y: 0.0, width: 10.0, height: 20.0 });
+        // 180度: 右下
+        assert_eq!(bounds.rotated(RotationAngle::Rotate180, 200.0, 100.0), TextBounds { x: 180.0, y: 90.0, width: 20.0, height: 10.0 });
+        // 270度: 左下
+        assert_eq!(bounds.rotated(RotationAngle::Rotate270, 200.0, 100.0), TextBounds { x: 0.0, y: 180.0, width: 10.0, height: 20.0 });
+    }
+
+    #[test]
+    fn rotated_bounds_agree_with_rotate_fraction() {
+        let (page_width, page_height) = (200.0, 100.0);
+        let bounds = TextBounds { x: 30.0, y: 20.0, width: 40.0, height: 10.0 };
+        for rotation in ANGLES {
+            let rotated = bounds.rotated(rotation, page_width, page_height);
+            let (displayed_width, displayed_height) = match rotation {
+                RotationAngle::Rotate90 | RotationAngle::Rotate270 => (page_height, page_width),
+                _ => (page_width, page_height),
+            };
+            let center = (
+                (bounds.x + bounds.width / 2.0) / page_width,
+                (bounds.y + bounds.height / 2.0) / page_height,
+            );
+            assert_close(
+                rotation.rotate_fraction(center.0, center.1),
+                (
+                    (rotated.x + rotated.width / 2.0) / displayed_width,
+                    (rotated.y + rotated.height / 2.0) / displayed_height,
+                ),
+            );
+            assert_eq!(rotated.unrotated_size(rotation), (bounds.width, bounds.height));
+        }
+    }
+
+    #[test]
+    fn next_cycles_through_all_angles() {
+        let mut rotation = RotationAngle::None;
+        for expected in [90.0, 180.0, 270.0, 0.0] {
+            rotation = rotation.next();
+            assert_eq!(rotation.to_degrees(), expected);
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use super::pdf::RotationAngle;
+use super::pdf::{PdfLink, RotationAngle, TextElement};
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct FlashCard {
@@ -45,8 +45,8 @@ pub struct ReadingBookmark {
 pub struct PositionMarker {
     pub id: String,
     pub page_index: usize,            // ページ番号（0から始まる）
-    pub x: f32,                       // ページ内のX座標（相対位置 0.0-1.0）
-    pub y: f32,                       // ページ内のY座標（相対位置 0.0-1.0）
+    pub x: f32,                       // 回転前のページ内のX座標（相対位置 0.0-1.0）
+    pub y: f32,                       // 回転前のページ内のY座標（相対位置 0.0-1.0）
     pub created_at: String,           // 作成日時
     pub note: String,                 // オプションのメモ
 }
@@ -62,6 +62,11 @@ impl PositionMarker {
             note,
         }
     }
+    
+    // 現在の回転で表示したページ上の相対位置
+    pub fn displayed_position(&self, rotation: RotationAngle) -> (f32, f32) {
+        rotation.rotate_fraction(self.x, self.y)
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -69,4 +74,65 @@ pub struct PdfMarkers {
     pub pdf_path: String,
     pub markers: Vec<PositionMarker>,
     pub last_modified: String,
-}
\ No newline at end of file
+    #[serde(default)]
+    pub format_version: u32, // 0: 表示上（回転後）の座標、1: 回転前のページの座標
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ViewerSettings {
+    pub page_cache_budget_mb: usize, // レンダリング済みページを保持するメモリの上限（MB）
+    #[serde(default = "default_render_cache_limit_mb")]
+    pub render_cache_limit_mb: u64,  // ディスク上のレンダリングキャッシュの上限（MB）
+    #[serde(default)]
+    pub library_folder: Option<String>, // 横断検索のインデックスに含めるフォルダ
+}
+
+pub fn default_render_cache_limit_mb() -> u64 {
+    1024
+}
+
+// ディスクに保存するレンダリング結果（画像は別ファイルのPNG）
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CachedPageRender {
+    pub page_width: f32,
+    pub page_height: f32,
+    pub text_elements: Vec<TextElement>,
+    pub links: Vec<PdfLink>,
+    #[serde(default)]
+    pub format_version: u32, // テキストの抽出方法が変わった場合に古いキャッシュを使わないための版数
+}
+
+// 横断検索用の転置インデックス（語 -> 出現するページ）
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct LibraryIndex {
+    #[serde(default)]
+    pub version: u32, // 索引の作り方を変えたときに古い索引を作り直すための版
+    pub documents: Vec<IndexedDocument>,
+    pub postings: HashMap<String, Vec<IndexPosting>>,
+    pub next_document_id: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct IndexedDocument {
+    pub id: u32,
+    pub path: String,
+    pub display_name: String,
+    pub file_size: u64,
+    pub modified: u64, // 更新日時（UNIX時間の秒）。サイズと合わせて変更の検出に使う
+    pub page_count: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct IndexPosting {
+    pub document_id: u32,
+    pub page_index: u32,
+    pub count: u32, // ページ内での出現回数
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LibraryHit {
+    pub path: String,
+    pub display_name: String,
+    pub page_index: usize,
+    pub score: f32,
+}
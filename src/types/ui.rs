@@ -1,6 +1,95 @@
+use crate::pdf::PageImage;
+use super::pdf::RotationAngle;
+
 #[derive(Clone, PartialEq)]
 pub enum AIProvider {
     Gemini,
     ChatGPT,
     Claude,
-}
\ No newline at end of file
+}
+
+// 1ポイント（1/72インチ）あたりのCSSピクセル数（CSSは96dpi基準）
+pub const CSS_PIXELS_PER_POINT: f32 = 96.0 / 72.0;
+
+// ズームイン・ズームアウトで切り替える倍率（％）
+pub const ZOOM_STEPS: [u32; 11] = [25, 50, 75, 100, 125, 150, 200, 300, 400, 600, 800];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ZoomLevel {
+    FitWidth,     // 幅に合わせる
+    FitPage,      // ページ全体を表示
+    Percent(u32), // 実寸に対する倍率（％）
+}
+
+// PDFビューアー領域の大きさ（CSSピクセル）とデバイスピクセル比
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ViewportSize {
+    pub width: f32,
+    pub height: f32,
+    pub device_pixel_ratio: f32,
+}
+
+impl Default for ViewportSize {
+    fn default() -> Self {
+        Self {
+            width: 800.0,
+            height: 600.0,
+            device_pixel_ratio: 1.0,
+        }
+    }
+}
+
+impl ZoomLevel {
+    // ページの表示幅（CSSピクセル）を計算（page_width/page_heightは回転後のポイント単位の寸法）
+    pub fn display_width(self, page_width: f32, page_height: f32, viewport: ViewportSize) -> f32 {
+        // スクロールバーや余白、ページヘッダーの分を差し引く
+        let available_width = (viewport.width - 40.0).max(100.0);
+        let available_height = (viewport.height - 60.0).max(100.0);
+        
+        match self {
+            ZoomLevel::FitWidth => available_width,
+            ZoomLevel::FitPage => {
+                let scale = (available_width / page_width).min(available_height / page_height);
+                page_width * scale
+            }
+            ZoomLevel::Percent(percent) => page_width * CSS_PIXELS_PER_POINT * percent as f32 / 100.0,
+        }
+    }
+    
+    // 表示幅とデバイスピクセル比に合わせたレンダリング倍率（1ポイントあたりのピクセル数）
+    pub fn render_scale(self, page_width: f32, page_height: f32, viewport: ViewportSize) -> f32 {
+        let scale = self.display_width(page_width, page_height, viewport) * viewport.device_pixel_ratio / page_width;
+        // 小さな変化で再レンダリングが繰り返されないよう1/16刻みに丸める
+        ((scale * 16.0).round() / 16.0).max(1.0 / 16.0)
+    }
+    
+    // 現在の表示倍率（％）
+    pub fn effective_percent(self, page_width: f32, page_height: f32, viewport: ViewportSize) -> u32 {
+        let display_width = self.display_width(page_width, page_height, viewport);
+        (display_width / (page_width * CSS_PIXELS_PER_POINT) * 100.0).round() as u32
+    }
+    
+    pub fn zoom_in(self, page_width: f32, page_height: f32, viewport: ViewportSize) -> Self {
+        let current = self.effective_percent(page_width, page_height, viewport);
+        let next = ZOOM_STEPS.iter().copied().find(|&step| step > current).unwrap_or(ZOOM_STEPS[ZOOM_STEPS.len() - 1]);
+        ZoomLevel::Percent(next)
+    }
+    
+    pub fn zoom_out(self, page_width: f32, page_height: f32, viewport: ViewportSize) -> Self {
+        let current = self.effective_percent(page_width, page_height, viewport);
+        let next = ZOOM_STEPS.iter().rev().copied().find(|&step| step < current).unwrap_or(ZOOM_STEPS[0]);
+        ZoomLevel::Percent(next)
+    }
+}
+
+// リンクにマウスを重ねたときに表示する移動先のプレビュー
+#[derive(Clone, Debug, PartialEq)]
+pub struct LinkPreview {
+    pub page_index: usize,
+    pub x: Option<f32>,              // 移動先のページ左端からの位置（回転前のポイント単位）
+    pub y: Option<f32>,              // 移動先のページ上端からの位置（回転前のポイント単位）
+    pub rotation: RotationAngle,     // 移動先のページの表示上の回転
+    pub client_x: f64,               // プレビューを表示する位置（ウィンドウ座標）
+    pub client_y: f64,
+    pub image: Option<PageImage>,    // レンダリングが完了するまではNone
+}
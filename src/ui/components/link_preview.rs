@@ -0,0 +1,32 @@
+use dioxus::prelude::*;
+use crate::LinkPreview;
+
+// 文書内リンクの移動先を切り出したプレビュー（マウスカーソルの近くに表示）
+#[component]
+pub fn link_preview_popup(link_preview: Signal<Option<LinkPreview>>) -> Element {
+    let Some(preview) = link_preview() else {
+        return rsx! {};
+    };
+
+    rsx! {
+        div {
+            class: "link-preview",
+            style: "position: fixed; left: {preview.client_x + 16.0}px; top: {preview.client_y + 16.0}px; width: 360px; background-color: white; border: 1px solid #bdc3c7; border-radius: 4px; box-shadow: 0 4px 12px rgba(0, 0, 0, 0.25); z-index: 900; pointer-events: none; overflow: hidden;",
+            div {
+                style: "padding: 4px 8px; font-size: 12px; color: #7f8c8d; background-color: #ecf0f1; border-bottom: 1px solid #bdc3c7;",
+                "ページ {preview.page_index + 1}"
+            }
+            if let Some(image) = &preview.image {
+                img {
+                    src: "{image}",
+                    style: "display: block; width: 100%; height: auto;",
+                }
+            } else {
+                div {
+                    style: "padding: 20px; text-align: center; color: #3498db; font-style: italic; font-size: 13px;",
+                    "読み込み中..."
+                }
+            }
+        }
+    }
+}
@@ -1 +1,6 @@
-pub mod popups;
\ No newline at end of file
+pub mod link_preview;
+pub mod outline;
+pub mod page_organizer;
+pub mod popups;
+pub mod search;
+pub mod thumbnails;
\ No newline at end of file
@@ -0,0 +1,109 @@
+use dioxus::prelude::*;
+use crate::OutlineItem;
+
+#[component]
+pub fn outline_panel(
+    outline: Memo<Vec<OutlineItem>>,
+    current_page: Signal<usize>,
+) -> Element {
+    rsx! {
+        div {
+            class: "outline-panel",
+            style: "width: 250px; flex-shrink: 0; overflow-y: auto; background-color: #2c3e50; color: #ecf0f1; padding: 10px; height: 100%; max-height: calc(100vh - 200px); font-size: 13px;",
+            h3 {
+                style: "margin: 0 0 10px 0; font-size: 15px; border-bottom: 1px solid #34495e; padding-bottom: 8px;",
+                "📑 目次"
+            }
+            if outline.read().is_empty() {
+                div {
+                    style: "color: #bdc3c7; padding: 10px 0;",
+                    "このPDFには目次がありません"
+                }
+            } else {
+                for (item_idx, item) in outline.read().iter().enumerate() {
+                    outline_node {
+                        key: "{item_idx}",
+                        item: item.clone(),
+                        depth: 0,
+                        current_page: current_page,
+                    }
+                }
+            }
+        }
+    }
+}
+
+// 目次の1項目（子を持つ場合は折りたたみ可能）
+#[component]
+fn outline_node(
+    item: OutlineItem,
+    depth: usize,
+    current_page: Signal<usize>,
+) -> Element {
+    let mut is_expanded = use_signal(|| false);
+    let has_children = !item.children.is_empty();
+    let page_index = item.page_index;
+    let is_current = page_index == Some(current_page());
+
+    rsx! {
+        div {
+            div {
+                class: "outline-item",
+                style: format!(
+                    "display: flex; align-items: flex-start; gap: 4px; padding: 4px 4px 4px {}px; border-radius: 3px; cursor: pointer; background-color: {};",
+                    depth * 14 + 4,
+                    if is_current { "#3498db" } else { "transparent" }
+                ),
+                span {
+                    style: "width: 14px; flex-shrink: 0; color: #95a5a6;",
+                    onclick: move |e| {
+                        e.stop_propagation();
+                        is_expanded.set(!is_expanded());
+                    },
+                    if has_children {
+                        if is_expanded() { "▼" } else { "▶" }
+                    }
+                }
+                span {
+                    style: "flex: 1; word-break: break-word;",
+                    title: match page_index {
+                        Some(page_index) => format!("ページ {} に移動", page_index + 1),
+                        None => String::new(),
+                    },
+                    onclick: move |_| {
+                        if let Some(page_index) = page_index {
+                            eval(&format!(
+                                r#"
+                                const element = document.getElementById('page-wrapper-{}');
+                                if (element) {{
+                                    element.scrollIntoView({{ behavior: 'smooth', block: 'start' }});
+                                }}
+                                "#,
+                                page_index
+                            ));
+                        } else if has_children {
+                            is_expanded.set(!is_expanded());
+                        }
+                    },
+                    "{item.title}"
+                }
+                if let Some(page_index) = page_index {
+                    span {
+                        style: "flex-shrink: 0; color: #95a5a6; font-size: 11px;",
+                        "{page_index + 1}"
+                    }
+                }
+            }
+            if is_expanded() {
+                for (child_idx, child) in item.children.iter().enumerate() {
+                    outline_node {
+                        key: "{child_idx}",
+                        item: child.clone(),
+                        depth: depth + 1,
+                        current_page: current_page,
+                    }
+                }
+            }
+        }
+    }
+}
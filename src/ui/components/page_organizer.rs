@@ -0,0 +1,572 @@
+use dioxus::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use crate::{
+    OrganizerPage, OrganizerPageSource, PageImage, RotationAngle, get_page_sizes, release_pdf_document,
+    render_thumbnail_in_background, save_organized_pages,
+};
+
+// プレビューの要求に使うキー（文書のパス, ページ番号, 回転）
+type PreviewKey = (String, usize, RotationAngle);
+
+// 新しいページを挿入する位置（最後に選択したページの後ろ、選択がなければ末尾）
+fn insert_position(pages: &[OrganizerPage], selected: &HashSet<u64>) -> usize {
+    pages
+        .iter()
+        .rposition(|page| selected.contains(&page.id))
+        .map_or(pages.len(), |position| position + 1)
+}
+
+// 並び替え（ドラッグしたページを指定したページの位置に移動）
+fn move_page(pages: &mut Vec<OrganizerPage>, page_id: u64, target_id: u64) {
+    if page_id == target_id {
+        return;
+    }
+    let Some(from) = pages.iter().position(|page| page.id == page_id) else { return };
+    let page = pages.remove(from);
+    let to = pages.iter().position(|page| page.id == target_id).map_or(pages.len(), |to| if from <= to { to + 1 } else { to });
+    pages.insert(to, page);
+}
+
+// 選択したページを取り除く
+fn delete_pages(pages: &mut Vec<OrganizerPage>, selected: &HashSet<u64>) {
+    pages.retain(|page| !selected.contains(&page.id));
+}
+
+// 選択したページのすぐ後ろに、新しいidを付けた複製を並べる
+fn duplicate_pages(pages: &[OrganizerPage], selected: &HashSet<u64>, mut allocate_id: impl FnMut() -> u64) -> Vec<OrganizerPage> {
+    let mut duplicated = Vec::with_capacity(pages.len() + selected.len());
+    for page in pages {
+        duplicated.push(page.clone());
+        if selected.contains(&page.id) {
+            duplicated.push(OrganizerPage { id: allocate_id(), ..page.clone() });
+        }
+    }
+    duplicated
+}
+
+// 指定した位置にページをまとめて挿入
+fn insert_pages(pages: &mut Vec<OrganizerPage>, position: usize, inserted: Vec<OrganizerPage>) {
+    let following = pages.split_off(position.min(pages.len()));
+    pages.extend(inserted);
+    pages.extend(following);
+}
+
+#[component]
+pub fn page_organizer(
+    show_page_organizer: Signal<bool>,
+    loaded_pdf_path: Signal<Option<PathBuf>>,
+    page_sizes: Memo<Vec<(f32, f32)>>,
+    page_rotations: Signal<HashMap<usize, RotationAngle>>,
+    render_generation: Signal<u64>,
+) -> Element {
+    // 開いている文書のページを、保存されている回転を反映して並べる
+    let original_pages = move || {
+        let rotations = page_rotations.peek();
+        page_sizes
+            .peek()
+            .iter()
+            .enumerate()
+            .map(|(page_index, &(width, height))| OrganizerPage {
+                id: page_index as u64,
+                source: OrganizerPageSource::Original(page_index),
+                rotation: rotations.get(&page_index).copied().unwrap_or(RotationAngle::None),
+                width,
+                height,
+            })
+            .collect::<Vec<_>>()
+    };
+    let mut pages = use_signal(original_pages);
+    let mut next_id = use_signal(|| page_sizes.peek().len() as u64);
+
+    // ページの寸法は非同期に読み込まれるため、開いた時点で未取得なら届いてから並べる
+    use_effect(move || {
+        let page_count = page_sizes.read().len();
+        if *next_id.peek() == 0 && page_count > 0 {
+            pages.set(original_pages());
+            next_id.set(page_count as u64);
+        }
+    });
+
+    let mut selected = use_signal(|| HashSet::<u64>::new());
+    let mut dragged = use_signal(|| -> Option<u64> { None });
+    let mut previews = use_signal(|| HashMap::<PreviewKey, PageImage>::new());
+    let mut requested_previews = use_signal(|| HashSet::<PreviewKey>::new());
+    let mut visible_ids = use_signal(|| Vec::<u64>::new());
+    let mut imported_paths = use_signal(|| Vec::<String>::new());
+    let mut status = use_signal(|| String::new());
+    let mut is_saving = use_signal(|| false);
+
+    // 取り込んだ文書のハンドルは整理モードを閉じたら解放
+    use_drop(move || {
+        for path in imported_paths.peek().iter() {
+            release_pdf_document(path);
+        }
+    });
+
+    // 見えているページだけプレビューをレンダリングする
+    use_future(move || async move {
+        let mut visible_eval = eval(r#"
+            let scheduled = false;
+            const report = () => {
+                scheduled = false;
+                const container = document.querySelector('.page-organizer-grid');
+                if (!container) return;
+                const containerRect = container.getBoundingClientRect();
+                const margin = containerRect.height / 2;
+                const visible = [];
+                for (const element of container.querySelectorAll('[id^="organizer-page-"]')) {
+                    const rect = element.getBoundingClientRect();
+                    if (rect.bottom > containerRect.top - margin && rect.top < containerRect.bottom + margin) {
+                        visible.push(parseInt(element.id.substring('organizer-page-'.length)));
+                    }
+                }
+                dioxus.send({ visible: visible });
+            };
+            const scheduleReport = () => {
+                if (!scheduled) {
+                    scheduled = true;
+                    requestAnimationFrame(report);
+                }
+            };
+            document.addEventListener('scroll', scheduleReport, true);
+            window.addEventListener('resize', scheduleReport);
+            new MutationObserver(scheduleReport).observe(document.body, { childList: true, subtree: true });
+            scheduleReport();
+        "#);
+
+        while let Ok(value) = visible_eval.recv().await {
+            if let Some(visible) = value.get("visible").and_then(|visible| visible.as_array()) {
+                let visible: Vec<u64> = visible.iter().filter_map(|v| v.as_u64()).collect();
+                if *visible_ids.peek() != visible {
+                    visible_ids.set(visible);
+                }
+            }
+        }
+    });
+
+    use_effect(move || {
+        let visible = visible_ids();
+        let Some(pdf_path) = loaded_pdf_path() else { return };
+        let pdf_path = pdf_path.to_string_lossy().to_string();
+
+        for page in pages.read().iter().filter(|page| visible.contains(&page.id)) {
+            let Some((path, page_index)) = page.preview_source(&pdf_path) else { continue };
+            let key = (path.clone(), page_index, page.rotation);
+            if previews.peek().contains_key(&key) || requested_previews.peek().contains(&key) {
+                continue;
+            }
+
+            requested_previews.write().insert(key.clone());
+            let generation = *render_generation.peek();
+            spawn(async move {
+                let result = render_thumbnail_in_background(&path, page_index, key.2, generation).await;
+                requested_previews.write().remove(&key);
+                if let Ok(image) = result {
+                    previews.write().insert(key, image);
+                }
+            });
+        }
+    });
+
+    let mut allocate_id = move || {
+        let id = *next_id.peek();
+        next_id.set(id + 1);
+        id
+    };
+
+    // 保存先を選んで新しいファイルに書き出す（元のファイルは変更しない）
+    let mut save_pages = move |only_selected: bool| {
+        let Some(pdf_path) = loaded_pdf_path() else { return };
+        if *is_saving.peek() {
+            return;
+        }
+        let selected_ids = selected();
+        let pages_to_save: Vec<OrganizerPage> = pages().into_iter().filter(|page| !only_selected || selected_ids.contains(&page.id)).collect();
+        if pages_to_save.is_empty() {
+            status.set("保存するページがありません".to_string());
+            return;
+        }
+
+        spawn(async move {
+            let file_stem = pdf_path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+            let suffix = if only_selected { "extracted" } else { "organized" };
+            let mut dialog = rfd::AsyncFileDialog::new()
+                .add_filter("PDF files", &["pdf"])
+                .set_title("新しいファイルとして保存")
+                .set_file_name(format!("{}_{}.pdf", file_stem, suffix));
+            if let Some(directory) = pdf_path.parent() {
+                dialog = dialog.set_directory(directory);
+            }
+            let Some(file_handle) = dialog.save_file().await else { return };
+
+            is_saving.set(true);
+            status.set("保存しています…".to_string());
+            let result = save_organized_pages(&pdf_path.to_string_lossy(), &pages_to_save, file_handle.path(), |done, total| {
+                status.set(format!("保存しています… ({}/{}ページ)", done, total));
+            })
+            .await;
+            is_saving.set(false);
+            match result {
+                Ok(()) => status.set(format!("{}ページを保存しました: {}", pages_to_save.len(), file_handle.path().display())),
+                Err(e) => status.set(format!("保存できませんでした: {}", e)),
+            }
+        });
+    };
+
+    let selected_count = selected().len();
+    let pdf_path_str = loaded_pdf_path().map(|path| path.to_string_lossy().to_string()).unwrap_or_default();
+    let button_style = "padding: 6px 12px; background-color: #34495e; color: white; border: none; border-radius: 4px; cursor: pointer; font-size: 13px;";
+
+    rsx! {
+        div {
+            class: "page-organizer",
+            style: "position: fixed; top: 0; left: 0; width: 100%; height: 100%; background-color: #2c3e50; z-index: 900; display: flex; flex-direction: column;",
+
+            // ツールバー
+            div {
+                style: "display: flex; flex-wrap: wrap; align-items: center; gap: 8px; padding: 12px 16px; border-bottom: 1px solid #34495e;",
+                h2 {
+                    style: "color: #ecf0f1; margin: 0 12px 0 0; font-size: 18px;",
+                    "🗃 ページ整理"
+                }
+                span {
+                    style: "color: #bdc3c7; font-size: 13px; margin-right: 8px;",
+                    "{pages.read().len()}ページ（{selected_count}ページ選択中）"
+                }
+                button {
+                    style: "{button_style}",
+                    onclick: move |_| {
+                        let all_ids: HashSet<u64> = pages.peek().iter().map(|page| page.id).collect();
+                        if *selected.peek() == all_ids {
+                            selected.write().clear();
+                        } else {
+                            selected.set(all_ids);
+                        }
+                    },
+                    "全て選択 / 解除"
+                }
+                button {
+                    style: "{button_style}",
+                    disabled: selected_count == 0,
+                    onclick: move |_| {
+                        delete_pages(&mut pages.write(), &selected.peek());
+                        selected.write().clear();
+                    },
+                    "🗑 削除"
+                }
+                button {
+                    style: "{button_style}",
+                    disabled: selected_count == 0,
+                    onclick: move |_| {
+                        let duplicated = duplicate_pages(&pages.peek(), &selected.peek(), &mut allocate_id);
+                        pages.set(duplicated);
+                    },
+                    "⧉ 複製"
+                }
+                button {
+                    style: "{button_style}",
+                    disabled: selected_count == 0,
+                    onclick: move |_| {
+                        let selected_ids = selected();
+                        for page in pages.write().iter_mut().filter(|page| selected_ids.contains(&page.id)) {
+                            page.rotation = page.rotation.next();
+                        }
+                    },
+                    "🔄 右に回転"
+                }
+                button {
+                    style: "{button_style}",
+                    onclick: move |_| {
+                        // 挿入位置の直前のページと同じ大きさの空白ページ
+                        let position = insert_position(&pages.peek(), &selected.peek());
+                        let (width, height) = position
+                            .checked_sub(1)
+                            .and_then(|previous| pages.peek().get(previous).map(|page| (page.width, page.height)))
+                            .unwrap_or((595.0, 842.0));
+                        let page = OrganizerPage {
+                            id: allocate_id(),
+                            source: OrganizerPageSource::Blank,
+                            rotation: RotationAngle::None,
+                            width,
+                            height,
+                        };
+                        pages.write().insert(position, page);
+                    },
+                    "➕ 空白ページを挿入"
+                }
+                button {
+                    style: "{button_style}",
+                    onclick: move |_| {
+                        spawn(async move {
+                            let Some(file_handle) = rfd::AsyncFileDialog::new()
+                                .add_filter("PDF files", &["pdf"])
+                                .set_title("ページを取り込むPDFを選択")
+                                .pick_file()
+                                .await else { return };
+                            let path = file_handle.path().to_string_lossy().to_string();
+
+                            match get_page_sizes(&path).await {
+                                Ok(sizes) => {
+                                    let position = insert_position(&pages.peek(), &selected.peek());
+                                    let imported: Vec<OrganizerPage> = sizes
+                                        .into_iter()
+                                        .enumerate()
+                                        .map(|(page_index, (width, height))| OrganizerPage {
+                                            id: allocate_id(),
+                                            source: OrganizerPageSource::Imported { path: path.clone(), page_index },
+                                            rotation: RotationAngle::None,
+                                            width,
+                                            height,
+                                        })
+                                        .collect();
+                                    status.set(format!("{}ページを取り込みました", imported.len()));
+                                    insert_pages(&mut pages.write(), position, imported);
+                                    if !imported_paths.peek().contains(&path) {
+                                        imported_paths.write().push(path);
+                                    }
+                                }
+                                Err(e) => status.set(format!("PDFを読み込めませんでした: {}", e)),
+                            }
+                        });
+                    },
+                    "📥 他のPDFから挿入"
+                }
+                div { style: "flex: 1;" }
+                button {
+                    style: "padding: 6px 12px; background-color: #16a085; color: white; border: none; border-radius: 4px; cursor: pointer; font-size: 13px;",
+                    disabled: selected_count == 0 || is_saving(),
+                    onclick: move |_| save_pages(true),
+                    "📤 選択したページを抽出して保存"
+                }
+                button {
+                    style: "padding: 6px 12px; background-color: #27ae60; color: white; border: none; border-radius: 4px; cursor: pointer; font-size: 13px;",
+                    disabled: is_saving(),
+                    onclick: move |_| save_pages(false),
+                    "💾 新しいファイルとして保存"
+                }
+                button {
+                    style: "background: none; border: none; color: #e74c3c; cursor: pointer; font-size: 24px; padding: 0 0 0 8px;",
+                    onclick: move |_| {
+                        show_page_organizer.set(false);
+                    },
+                    "×"
+                }
+            }
+
+            if !status().is_empty() {
+                div {
+                    style: "padding: 6px 16px; color: #f1c40f; font-size: 13px;",
+                    "{status}"
+                }
+            }
+
+            // ページ一覧（クリックで選択、ドラッグで並び替え）
+            div {
+                class: "page-organizer-grid",
+                style: "flex: 1; overflow-y: auto; padding: 16px; display: flex; flex-wrap: wrap; gap: 16px; align-content: flex-start;",
+                for (position, page) in pages().into_iter().enumerate() {
+                    div {
+                        key: "{page.id}",
+                        id: "organizer-page-{page.id}",
+                        draggable: "true",
+                        prevent_default: "ondragover ondrop",
+                        style: format!(
+                            "width: 140px; cursor: grab; border: 3px solid {}; border-radius: 4px; background-color: #34495e; opacity: {};",
+                            if selected().contains(&page.id) { "#3498db" } else { "transparent" },
+                            if dragged() == Some(page.id) { "0.4" } else { "1" }
+                        ),
+                        onclick: move |_| {
+                            let mut selected = selected.write();
+                            if !selected.remove(&page.id) {
+                                selected.insert(page.id);
+                            }
+                        },
+                        ondragstart: move |_| dragged.set(Some(page.id)),
+                        ondragend: move |_| dragged.set(None),
+                        ondragover: move |_| {},
+                        ondrop: move |_| {
+                            if let Some(dragged_id) = dragged() {
+                                move_page(&mut pages.write(), dragged_id, page.id);
+                            }
+                            dragged.set(None);
+                        },
+                        {
+                            let preview = page
+                                .preview_source(&pdf_path_str)
+                                .and_then(|(path, page_index)| previews.read().get(&(path, page_index, page.rotation)).map(|image| image.url()));
+                            rsx! {
+                                div {
+                                    style: "width: 100%; aspect-ratio: {page.aspect_ratio()}; background-color: white; display: flex; align-items: center; justify-content: center; color: #bdc3c7; font-size: 12px;",
+                                    if let Some(url) = preview {
+                                        img {
+                                            src: "{url}",
+                                            draggable: "false",
+                                            style: "display: block; width: 100%; height: 100%;",
+                                        }
+                                    } else if page.source == OrganizerPageSource::Blank {
+                                        "空白ページ"
+                                    } else {
+                                        "…"
+                                    }
+                                }
+                            }
+                        }
+                        div {
+                            style: "display: flex; align-items: center; justify-content: space-between; padding: 4px; font-size: 11px; color: #ecf0f1;",
+                            button {
+                                style: "background: none; border: none; color: #ecf0f1; cursor: pointer; padding: 0 4px;",
+                                disabled: position == 0,
+                                onclick: move |e| {
+                                    e.stop_propagation();
+                                    pages.write().swap(position, position - 1);
+                                },
+                                "◀"
+                            }
+                            span {
+                                style: "text-align: center; overflow: hidden; text-overflow: ellipsis; white-space: nowrap;",
+                                title: match &page.source {
+                                    OrganizerPageSource::Imported { path, .. } => path.clone(),
+                                    _ => String::new(),
+                                },
+                                {match &page.source {
+                                    OrganizerPageSource::Original(page_index) => format!("{}（元 P.{}）", position + 1, page_index + 1),
+                                    OrganizerPageSource::Imported { page_index, .. } => format!("{}（取込 P.{}）", position + 1, page_index + 1),
+                                    OrganizerPageSource::Blank => format!("{}（空白）", position + 1),
+                                }}
+                            }
+                            button {
+                                style: "background: none; border: none; color: #ecf0f1; cursor: pointer; padding: 0 4px;",
+                                disabled: position + 1 >= pages.read().len(),
+                                onclick: move |e| {
+                                    e.stop_propagation();
+                                    pages.write().swap(position, position + 1);
+                                },
+                                "▶"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn original_pages(count: usize) -> Vec<OrganizerPage> {
+        (0..count)
+            .map(|page_index| OrganizerPage {
+                id: page_index as u64,
+                source: OrganizerPageSource::Original(page_index),
+                rotation: RotationAngle::None,
+                width: 595.0,
+                height: 842.0,
+            })
+            .collect()
+    }
+
+    // 並んでいるページの出どころを短い文字列にする（元のP.1なら"o1"、取り込んだP.1なら"i1"、空白は"b"）
+    fn order(pages: &[OrganizerPage]) -> Vec<String> {
+        pages
+            .iter()
+            .map(|page| match &page.source {
+                OrganizerPageSource::Original(page_index) => format!("o{}", page_index + 1),
+                OrganizerPageSource::Imported { page_index, .. } => format!("i{}", page_index + 1),
+                OrganizerPageSource::Blank => "b".to_string(),
+            })
+            .collect()
+    }
+
+    fn ids(values: &[u64]) -> HashSet<u64> {
+        values.iter().copied().collect()
+    }
+
+    #[test]
+    fn moves_pages_before_or_after_the_target() {
+        let mut pages = original_pages(4);
+        move_page(&mut pages, 0, 2);
+        assert_eq!(order(&pages), ["o2", "o3", "o1", "o4"]);
+        move_page(&mut pages, 3, 1);
+        assert_eq!(order(&pages), ["o4", "o2", "o3", "o1"]);
+        move_page(&mut pages, 1, 1);
+        move_page(&mut pages, 9, 1);
+        assert_eq!(order(&pages), ["o4", "o2", "o3", "o1"]);
+    }
+
+    #[test]
+    fn duplicates_selected_pages_in_place_with_new_ids() {
+        let pages = original_pages(3);
+        let mut next_id = 10;
+        let duplicated = duplicate_pages(&pages, &ids(&[0, 2]), || {
+            next_id += 1;
+            next_id
+        });
+        assert_eq!(order(&duplicated), ["o1", "o1", "o2", "o3", "o3"]);
+        assert_eq!(duplicated.iter().map(|page| page.id).collect::<Vec<_>>(), [0, 11, 1, 2, 12]);
+    }
+
+    #[test]
+    fn inserts_after_the_last_selected_page() {
+        let pages = original_pages(4);
+        assert_eq!(insert_position(&pages, &ids(&[0, 2])), 3);
+        assert_eq!(insert_position(&pages, &ids(&[])), 4);
+        assert_eq!(insert_position(&pages, &ids(&[3])), 4);
+    }
+
+    #[test]
+    fn applies_a_sequence_of_operations() {
+        let mut pages = original_pages(5);
+        let mut next_id = 5;
+        let mut allocate_id = || {
+            let id = next_id;
+            next_id += 1;
+            id
+        };
+
+        // P.2とP.4を削除
+        delete_pages(&mut pages, &ids(&[1, 3]));
+        assert_eq!(order(&pages), ["o1", "o3", "o5"]);
+
+        // P.5を先頭へ移動
+        move_page(&mut pages, 4, 0);
+        assert_eq!(order(&pages), ["o5", "o1", "o3"]);
+
+        // P.1を複製
+        pages = duplicate_pages(&pages, &ids(&[0]), &mut allocate_id);
+        assert_eq!(order(&pages), ["o5", "o1", "o1", "o3"]);
+
+        // 複製したP.1の後ろに、別のPDFの2ページを取り込む
+        let duplicate_id = pages[2].id;
+        let position = insert_position(&pages, &ids(&[duplicate_id]));
+        let imported = (0..2)
+            .map(|page_index| OrganizerPage {
+                id: allocate_id(),
+                source: OrganizerPageSource::Imported { path: "other.pdf".to_string(), page_index },
+                rotation: RotationAngle::None,
+                width: 612.0,
+                height: 792.0,
+            })
+            .collect();
+        insert_pages(&mut pages, position, imported);
+        assert_eq!(order(&pages), ["o5", "o1", "o1", "i1", "i2", "o3"]);
+
+        // 末尾に空白ページを追加
+        let blank = OrganizerPage { id: allocate_id(), source: OrganizerPageSource::Blank, rotation: RotationAngle::None, width: 612.0, height: 792.0 };
+        insert_pages(&mut pages, insert_position(&pages, &ids(&[])), vec![blank]);
+        assert_eq!(order(&pages), ["o5", "o1", "o1", "i1", "i2", "o3", "b"]);
+
+        // 全てのページのidが重ならない
+        let unique: HashSet<u64> = pages.iter().map(|page| page.id).collect();
+        assert_eq!(unique.len(), pages.len());
+    }
+
+    #[test]
+    fn insert_position_past_the_end_appends() {
+        let mut pages = original_pages(2);
+        insert_pages(&mut pages, 10, original_pages(1));
+        assert_eq!(order(&pages), ["o1", "o2", "o1"]);
+    }
+}
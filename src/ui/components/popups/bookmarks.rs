@@ -1,13 +1,12 @@
 use dioxus::prelude::*;
 use std::path::PathBuf;
-use std::collections::HashMap;
-use crate::{get_all_reading_bookmarks, delete_reading_bookmark, add_recent_file, load_recent_files, RecentFile, PdfPageData, ReadingBookmark};
+use crate::{get_all_reading_bookmarks, delete_reading_bookmark, add_recent_file, load_recent_files, release_pdf_document, RecentFile, PageCache, ReadingBookmark};
 
 #[component]
 pub fn bookmarks_popup(
     show_bookmarks_popup: Signal<bool>,
     pdf_path: Signal<Option<PathBuf>>,
-    page_cache: Signal<HashMap<usize, PdfPageData>>,
+    page_cache: Signal<PageCache>,
     loaded_pdf_path: Signal<Option<PathBuf>>,
     is_loading: Signal<bool>,
     recent_files: Signal<Vec<RecentFile>>,
@@ -108,6 +107,9 @@ pub fn bookmarks_popup(
                                                             .to_string();
                                                         let _ = add_recent_file(path.to_string_lossy().to_string(), file_name);
                                                         recent_files.set(load_recent_files());
+                                                        if let Some(previous_path) = pdf_path() {
+                                                            release_pdf_document(&previous_path.to_string_lossy());
+                                                        }
                                                         pdf_path.set(Some(path));
                                                         page_cache.write().clear();
                                                         loaded_pdf_path.set(None);
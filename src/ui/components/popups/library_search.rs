@@ -0,0 +1,292 @@
+use dioxus::prelude::*;
+use std::path::PathBuf;
+use crate::{
+    LibraryIndex, PageCache, RecentFile, add_recent_file, extract_document_text_in_background, has_document_password,
+    library_sources, load_library_index, load_recent_files, load_viewer_settings, release_pdf_document,
+    save_library_index, save_viewer_settings,
+};
+
+// 一覧に表示する検索結果の上限
+const MAX_LIBRARY_HITS: usize = 100;
+
+#[component]
+pub fn library_search_popup(
+    show_library_search_popup: Signal<bool>,
+    pdf_path: Signal<Option<PathBuf>>,
+    page_cache: Signal<PageCache>,
+    loaded_pdf_path: Signal<Option<PathBuf>>,
+    is_loading: Signal<bool>,
+    recent_files: Signal<Vec<RecentFile>>,
+    render_generation: Signal<u64>,
+) -> Element {
+    let mut library_index = use_signal(|| LibraryIndex::default());
+    let mut query = use_signal(|| String::new());
+    let mut is_indexing = use_signal(|| false);
+    let mut index_status = use_signal(|| String::new());
+    let mut library_folder = use_signal(|| load_viewer_settings().library_folder);
+
+    // 変更があった文書だけを抽出し直してインデックスを更新
+    let mut update_index = move || {
+        if *is_indexing.peek() {
+            return;
+        }
+        is_indexing.set(true);
+
+        spawn(async move {
+            // フォルダの走査や索引の読み込みはファイルアクセスが多いため、UIスレッドの外で行う
+            let prepared = tokio::task::spawn_blocking(|| {
+                let sources = library_sources(&load_viewer_settings());
+                let mut index = load_library_index();
+                let changed = index.remove_missing(&sources);
+                let stale = index.stale_documents(&sources);
+                (index, changed, stale)
+            })
+            .await;
+            let (mut index, mut changed, stale) = match prepared {
+                Ok(prepared) => prepared,
+                Err(e) => {
+                    index_status.set(format!("インデックスを読み込めませんでした: {}", e));
+                    is_indexing.set(false);
+                    return;
+                }
+            };
+            library_index.set(index.clone());
+
+            let mut failed = 0;
+            for (position, (path, display_name)) in stale.iter().enumerate() {
+                // 復号した内容はディスクに残さない
+                if has_document_password(path) {
+                    continue;
+                }
+                index_status.set(format!("インデックスを更新中… ({}/{}) {}", position + 1, stale.len(), display_name));
+
+                match extract_document_text_in_background(path, *render_generation.peek()).await {
+                    Ok(pages) => {
+                        let mut updating = std::mem::take(&mut index);
+                        let (path, display_name) = (path.clone(), display_name.clone());
+                        let updated = tokio::task::spawn_blocking(move || {
+                            updating.add_document(&path, &display_name, &pages);
+                            updating
+                        })
+                        .await;
+                        match updated {
+                            Ok(updated) => {
+                                index = updated;
+                                changed = true;
+                            }
+                            Err(e) => {
+                                // 保存済みの索引はそのまま残す
+                                index_status.set(format!("インデックスを更新できませんでした: {}", e));
+                                is_indexing.set(false);
+                                return;
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("インデックスの作成に失敗しました ({}): {}", path, e);
+                        failed += 1;
+                    }
+                }
+
+                // 表示中でない文書のハンドルはワーカーに残さない
+                let is_open = pdf_path.peek().as_ref().map_or(false, |open_path| open_path.to_string_lossy() == path.as_str());
+                if !is_open {
+                    release_pdf_document(path);
+                }
+            }
+
+            if changed {
+                let saved_index = index.clone();
+                if let Ok(Err(e)) = tokio::task::spawn_blocking(move || save_library_index(&saved_index)).await {
+                    eprintln!("インデックスを保存できませんでした: {}", e);
+                }
+            }
+
+            if failed > 0 {
+                index_status.set(format!("{}件の文書を索引済み（{}件は読み込めませんでした）", index.documents.len(), failed));
+            } else {
+                index_status.set(format!("{}件の文書を索引済み", index.documents.len()));
+            }
+            library_index.set(index);
+            is_indexing.set(false);
+        });
+    };
+
+    // 開いた時点でインデックスを最新にする
+    use_hook(move || update_index());
+
+    let hits = use_memo(move || library_index.read().search(&query(), MAX_LIBRARY_HITS));
+
+    rsx! {
+        div {
+            class: "popup-overlay",
+            style: "position: fixed; top: 0; left: 0; width: 100%; height: 100%; background-color: rgba(0, 0, 0, 0.7); display: flex; align-items: center; justify-content: center; z-index: 1000;",
+            onclick: move |_| {
+                show_library_search_popup.set(false);
+            },
+            div {
+                class: "popup-content",
+                style: "background-color: #2c3e50; border-radius: 8px; padding: 20px; min-width: 500px; max-width: 700px; max-height: 80vh; overflow-y: auto; position: relative;",
+                onclick: move |e| {
+                    e.stop_propagation();
+                },
+
+                // ヘッダー
+                div {
+                    style: "display: flex; justify-content: space-between; align-items: center; margin-bottom: 20px; border-bottom: 1px solid #34495e; padding-bottom: 10px;",
+                    h2 {
+                        style: "color: #ecf0f1; margin: 0; font-size: 18px;",
+                        "🗂 全ての文書を検索"
+                    }
+                    button {
+                        style: "background: none; border: none; color: #e74c3c; cursor: pointer; font-size: 24px; padding: 0;",
+                        onclick: move |_| {
+                            show_library_search_popup.set(false);
+                        },
+                        "×"
+                    }
+                }
+
+                input {
+                    r#type: "text",
+                    placeholder: "最近開いたファイルと監視フォルダ内のPDFを検索",
+                    value: "{query}",
+                    autofocus: true,
+                    style: "width: 100%; box-sizing: border-box; padding: 8px; border: 1px solid #34495e; border-radius: 4px; font-size: 14px; margin-bottom: 10px;",
+                    oninput: move |evt| query.set(evt.value()),
+                }
+
+                // 監視フォルダとインデックスの状態
+                div {
+                    style: "display: flex; align-items: center; gap: 8px; margin-bottom: 15px; font-size: 12px; color: #bdc3c7;",
+                    span {
+                        style: "flex: 1; overflow: hidden; text-overflow: ellipsis; white-space: nowrap;",
+                        {match library_folder() {
+                            Some(folder) => format!("監視フォルダ: {}", folder),
+                            None => "監視フォルダ: なし".to_string(),
+                        }}
+                    }
+                    button {
+                        style: "background-color: #34495e; color: white; border: none; border-radius: 4px; padding: 4px 8px; cursor: pointer; font-size: 12px;",
+                        onclick: move |_| {
+                            spawn(async move {
+                                if let Some(folder) = rfd::AsyncFileDialog::new()
+                                    .set_title("インデックスに含めるフォルダを選択")
+                                    .pick_folder()
+                                    .await
+                                {
+                                    let folder = folder.path().to_string_lossy().to_string();
+                                    let mut settings = load_viewer_settings();
+                                    settings.library_folder = Some(folder.clone());
+                                    let _ = save_viewer_settings(&settings);
+                                    library_folder.set(Some(folder));
+                                    update_index();
+                                }
+                            });
+                        },
+                        "📁 フォルダを選択"
+                    }
+                    if library_folder().is_some() {
+                        button {
+                            style: "background-color: #34495e; color: white; border: none; border-radius: 4px; padding: 4px 8px; cursor: pointer; font-size: 12px;",
+                            onclick: move |_| {
+                                let mut settings = load_viewer_settings();
+                                settings.library_folder = None;
+                                let _ = save_viewer_settings(&settings);
+                                library_folder.set(None);
+                                update_index();
+                            },
+                            "解除"
+                        }
+                    }
+                    button {
+                        style: "background-color: #3498db; color: white; border: none; border-radius: 4px; padding: 4px 8px; cursor: pointer; font-size: 12px;",
+                        disabled: is_indexing(),
+                        onclick: move |_| update_index(),
+                        "🔄 更新"
+                    }
+                }
+                div {
+                    style: "font-size: 12px; color: #95a5a6; margin-bottom: 10px;",
+                    "{index_status}"
+                }
+
+                if query().trim().is_empty() {
+                    div {
+                        style: "color: #bdc3c7; text-align: center; padding: 20px;",
+                        "検索する語を入力してください"
+                    }
+                } else if hits().is_empty() {
+                    div {
+                        style: "color: #bdc3c7; text-align: center; padding: 20px;",
+                        "一致するページはありません"
+                    }
+                } else {
+                    div {
+                        style: "display: flex; flex-direction: column; gap: 8px;",
+                        for hit in hits().into_iter() {
+                            div {
+                                key: "{hit.path}-{hit.page_index}",
+                                style: "background-color: #34495e; border-radius: 6px; padding: 10px 12px; cursor: pointer;",
+                                onclick: {
+                                    let hit_path = hit.path.clone();
+                                    let page_index = hit.page_index;
+                                    move |_| {
+                                        let path = PathBuf::from(&hit_path);
+                                        if !path.exists() {
+                                            return;
+                                        }
+
+                                        // 別のファイルの場合は開き直す
+                                        if pdf_path() != Some(path.clone()) {
+                                            let file_name = path.file_name()
+                                                .and_then(|n| n.to_str())
+                                                .unwrap_or("Unknown")
+                                                .to_string();
+                                            let _ = add_recent_file(path.to_string_lossy().to_string(), file_name);
+                                            recent_files.set(load_recent_files());
+                                            if let Some(previous_path) = pdf_path() {
+                                                release_pdf_document(&previous_path.to_string_lossy());
+                                            }
+                                            pdf_path.set(Some(path));
+                                            page_cache.write().clear();
+                                            loaded_pdf_path.set(None);
+                                            is_loading.set(false);
+                                        }
+                                        show_library_search_popup.set(false);
+
+                                        // ページ要素が描画されるまで待ってからスクロール
+                                        eval(&format!(
+                                            r#"
+                                            let attempts = 0;
+                                            const scrollToPage = () => {{
+                                                const element = document.getElementById('page-wrapper-{}');
+                                                if (element) {{
+                                                    element.scrollIntoView({{ behavior: 'smooth', block: 'start' }});
+                                                }} else if (attempts++ < 50) {{
+                                                    setTimeout(scrollToPage, 100);
+                                                }}
+                                            }};
+                                            setTimeout(scrollToPage, 200);
+                                            "#,
+                                            page_index
+                                        ));
+                                    }
+                                },
+                                div {
+                                    style: "font-weight: bold; color: #3498db; font-size: 14px; margin-bottom: 4px;",
+                                    "{hit.display_name}"
+                                }
+                                div {
+                                    style: "display: flex; justify-content: space-between; font-size: 12px; color: #95a5a6;",
+                                    span { "ページ {hit.page_index + 1}" }
+                                    span { "スコア {hit.score:.2}" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
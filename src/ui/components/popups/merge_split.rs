@@ -0,0 +1,344 @@
+use dioxus::prelude::*;
+use std::path::PathBuf;
+use crate::{SplitMode, merge_pdfs, split_pdf};
+
+// 分割方法の選択肢
+#[derive(Clone, Copy, PartialEq)]
+enum SplitKind {
+    PageRanges,
+    EveryPages,
+    TopLevelOutline,
+}
+
+#[component]
+pub fn merge_split_popup(
+    show_merge_split_popup: Signal<bool>,
+    pdf_path: Signal<Option<PathBuf>>,
+) -> Element {
+    let mut merge_inputs = use_signal(|| Vec::<PathBuf>::new());
+    let mut split_source = use_signal(|| pdf_path.peek().clone());
+    let mut split_kind = use_signal(|| SplitKind::PageRanges);
+    let mut page_ranges = use_signal(|| String::new());
+    let mut pages_per_file = use_signal(|| "10".to_string());
+    let mut is_running = use_signal(|| false);
+    let mut progress = use_signal(|| None::<(usize, usize)>);
+    let mut status = use_signal(|| String::new());
+
+    // 結合の順番を入れ替える
+    let mut move_input = move |index: usize, offset: isize| {
+        let mut inputs = merge_inputs.write();
+        let target = index as isize + offset;
+        if target >= 0 && (target as usize) < inputs.len() {
+            inputs.swap(index, target as usize);
+        }
+    };
+
+    let run_merge = move |_| {
+        if *is_running.peek() {
+            return;
+        }
+        let inputs: Vec<String> = merge_inputs.peek().iter().map(|path| path.to_string_lossy().to_string()).collect();
+        spawn(async move {
+            let mut dialog = rfd::AsyncFileDialog::new()
+                .add_filter("PDF files", &["pdf"])
+                .set_title("結合したPDFの保存先")
+                .set_file_name("merged.pdf");
+            if let Some(directory) = inputs.first().and_then(|path| PathBuf::from(path).parent().map(|parent| parent.to_path_buf())) {
+                dialog = dialog.set_directory(directory);
+            }
+            let Some(file_handle) = dialog.save_file().await else { return };
+            let output_path = file_handle.path().to_path_buf();
+
+            is_running.set(true);
+            progress.set(None);
+            status.set("結合しています…".to_string());
+
+            // PDFiumの処理は所有スレッドで行われ、UIは少しずつ届く進捗を反映しながら完了を待つ
+            let result = merge_pdfs(&inputs, &output_path, |done, total| progress.set(Some((done, total)))).await;
+            match result {
+                Ok(()) => status.set(format!("結合したPDFを保存しました: {}", output_path.display())),
+                Err(e) => status.set(format!("結合できませんでした: {}", e)),
+            }
+            is_running.set(false);
+        });
+    };
+
+    let run_split = move |_| {
+        if *is_running.peek() {
+            return;
+        }
+        let Some(source) = split_source.peek().clone() else { return };
+        let mode = match *split_kind.peek() {
+            SplitKind::PageRanges => SplitMode::PageRanges(page_ranges.peek().clone()),
+            SplitKind::EveryPages => match pages_per_file.peek().trim().parse::<usize>() {
+                Ok(pages) if pages > 0 => SplitMode::EveryPages(pages),
+                _ => {
+                    status.set("分割するページ数には1以上の数を入力してください".to_string());
+                    return;
+                }
+            },
+            SplitKind::TopLevelOutline => SplitMode::TopLevelOutline,
+        };
+
+        spawn(async move {
+            let mut dialog = rfd::AsyncFileDialog::new().set_title("分割したPDFの保存先フォルダ");
+            if let Some(directory) = source.parent() {
+                dialog = dialog.set_directory(directory);
+            }
+            let Some(folder) = dialog.pick_folder().await else { return };
+            let output_dir = folder.path().to_path_buf();
+
+            is_running.set(true);
+            progress.set(None);
+            status.set("分割しています…".to_string());
+
+            let result = split_pdf(&source.to_string_lossy(), &mode, &output_dir, |done, total| progress.set(Some((done, total)))).await;
+            match result {
+                Ok(written) => {
+                    let folder_name = written
+                        .first()
+                        .and_then(|path| path.parent())
+                        .map(|parent| parent.display().to_string())
+                        .unwrap_or_default();
+                    status.set(format!("{}個のファイルに分割しました: {}", written.len(), folder_name));
+                }
+                Err(e) => status.set(format!("分割できませんでした: {}", e)),
+            }
+            is_running.set(false);
+        });
+    };
+
+    let section_style = "background-color: #34495e; border-radius: 6px; padding: 12px; margin-bottom: 15px; color: #ecf0f1;";
+    let button_style = "background-color: #3498db; color: white; border: none; border-radius: 4px; padding: 6px 12px; cursor: pointer; font-size: 13px;";
+    let small_button_style = "background-color: #2c3e50; color: white; border: none; border-radius: 4px; padding: 2px 8px; cursor: pointer; font-size: 12px;";
+    let input_style = "padding: 6px; border: 1px solid #2c3e50; border-radius: 4px; font-size: 13px;";
+
+    rsx! {
+        div {
+            class: "popup-overlay",
+            style: "position: fixed; top: 0; left: 0; width: 100%; height: 100%; background-color: rgba(0, 0, 0, 0.7); display: flex; align-items: center; justify-content: center; z-index: 1000;",
+            onclick: move |_| {
+                // 処理中は閉じない（進捗が見えなくなるため）
+                if !is_running() {
+                    show_merge_split_popup.set(false);
+                }
+            },
+            div {
+                class: "popup-content",
+                style: "background-color: #2c3e50; border-radius: 8px; padding: 20px; min-width: 500px; max-width: 700px; max-height: 80vh; overflow-y: auto; position: relative;",
+                onclick: move |e| {
+                    e.stop_propagation();
+                },
+
+                // ヘッダー
+                div {
+                    style: "display: flex; justify-content: space-between; align-items: center; margin-bottom: 20px; border-bottom: 1px solid #34495e; padding-bottom: 10px;",
+                    h2 {
+                        style: "color: #ecf0f1; margin: 0; font-size: 18px;",
+                        "📚 PDFの結合・分割"
+                    }
+                    button {
+                        style: "background: none; border: none; color: #e74c3c; cursor: pointer; font-size: 24px; padding: 0;",
+                        disabled: is_running(),
+                        onclick: move |_| {
+                            show_merge_split_popup.set(false);
+                        },
+                        "×"
+                    }
+                }
+
+                // 結合
+                div {
+                    style: "{section_style}",
+                    div {
+                        style: "display: flex; justify-content: space-between; align-items: center; margin-bottom: 10px;",
+                        h3 { style: "margin: 0; font-size: 15px;", "結合" }
+                        button {
+                            style: "{button_style}",
+                            disabled: is_running(),
+                            onclick: move |_| {
+                                spawn(async move {
+                                    if let Some(files) = rfd::AsyncFileDialog::new()
+                                        .add_filter("PDF files", &["pdf"])
+                                        .set_title("結合するPDFを選択")
+                                        .pick_files()
+                                        .await
+                                    {
+                                        merge_inputs.write().extend(files.into_iter().map(|file| file.path().to_path_buf()));
+                                    }
+                                });
+                            },
+                            "＋ PDFを追加"
+                        }
+                    }
+                    if merge_inputs().is_empty() {
+                        div {
+                            style: "color: #bdc3c7; font-size: 13px; padding: 8px 0;",
+                            "結合するPDFを追加してください（上から順に結合します）"
+                        }
+                    } else {
+                        div {
+                            style: "display: flex; flex-direction: column; gap: 4px; margin-bottom: 10px;",
+                            for (index, path) in merge_inputs().into_iter().enumerate() {
+                                div {
+                                    key: "{index}-{path.display()}",
+                                    style: "display: flex; align-items: center; gap: 6px; font-size: 13px;",
+                                    span { style: "color: #95a5a6; width: 24px; text-align: right;", "{index + 1}." }
+                                    span {
+                                        style: "flex: 1; overflow: hidden; text-overflow: ellipsis; white-space: nowrap;",
+                                        title: "{path.display()}",
+                                        {path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default()}
+                                    }
+                                    button {
+                                        style: "{small_button_style}",
+                                        disabled: is_running() || index == 0,
+                                        onclick: move |_| move_input(index, -1),
+                                        "↑"
+                                    }
+                                    button {
+                                        style: "{small_button_style}",
+                                        disabled: is_running() || index + 1 == merge_inputs().len(),
+                                        onclick: move |_| move_input(index, 1),
+                                        "↓"
+                                    }
+                                    button {
+                                        style: "{small_button_style}",
+                                        disabled: is_running(),
+                                        onclick: move |_| {
+                                            merge_inputs.write().remove(index);
+                                        },
+                                        "×"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    button {
+                        style: "{button_style} background-color: #27ae60;",
+                        disabled: is_running() || merge_inputs().len() < 2,
+                        onclick: run_merge,
+                        "結合して保存…"
+                    }
+                }
+
+                // 分割
+                div {
+                    style: "{section_style}",
+                    h3 { style: "margin: 0 0 10px 0; font-size: 15px;", "分割" }
+                    div {
+                        style: "display: flex; align-items: center; gap: 8px; margin-bottom: 10px; font-size: 13px;",
+                        span {
+                            style: "flex: 1; overflow: hidden; text-overflow: ellipsis; white-space: nowrap;",
+                            {match split_source() {
+                                Some(path) => format!("対象: {}", path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default()),
+                                None => "対象: なし".to_string(),
+                            }}
+                        }
+                        button {
+                            style: "{button_style}",
+                            disabled: is_running(),
+                            onclick: move |_| {
+                                spawn(async move {
+                                    if let Some(file) = rfd::AsyncFileDialog::new()
+                                        .add_filter("PDF files", &["pdf"])
+                                        .set_title("分割するPDFを選択")
+                                        .pick_file()
+                                        .await
+                                    {
+                                        split_source.set(Some(file.path().to_path_buf()));
+                                    }
+                                });
+                            },
+                            "📂 選択"
+                        }
+                    }
+                    div {
+                        style: "display: flex; flex-direction: column; gap: 6px; margin-bottom: 10px; font-size: 13px;",
+                        label {
+                            style: "display: flex; align-items: center; gap: 6px;",
+                            input {
+                                r#type: "radio",
+                                name: "split-kind",
+                                checked: split_kind() == SplitKind::PageRanges,
+                                onchange: move |_| split_kind.set(SplitKind::PageRanges),
+                            }
+                            "ページ範囲"
+                            input {
+                                r#type: "text",
+                                placeholder: "例: 1-3, 5, 8-",
+                                value: "{page_ranges}",
+                                style: "{input_style} flex: 1;",
+                                oninput: move |evt| {
+                                    page_ranges.set(evt.value());
+                                    split_kind.set(SplitKind::PageRanges);
+                                },
+                            }
+                        }
+                        label {
+                            style: "display: flex; align-items: center; gap: 6px;",
+                            input {
+                                r#type: "radio",
+                                name: "split-kind",
+                                checked: split_kind() == SplitKind::EveryPages,
+                                onchange: move |_| split_kind.set(SplitKind::EveryPages),
+                            }
+                            input {
+                                r#type: "number",
+                                min: "1",
+                                value: "{pages_per_file}",
+                                style: "{input_style} width: 70px;",
+                                oninput: move |evt| {
+                                    pages_per_file.set(evt.value());
+                                    split_kind.set(SplitKind::EveryPages);
+                                },
+                            }
+                            "ページごと"
+                        }
+                        label {
+                            style: "display: flex; align-items: center; gap: 6px;",
+                            input {
+                                r#type: "radio",
+                                name: "split-kind",
+                                checked: split_kind() == SplitKind::TopLevelOutline,
+                                onchange: move |_| split_kind.set(SplitKind::TopLevelOutline),
+                            }
+                            "最上位のしおりごと"
+                        }
+                    }
+                    button {
+                        style: "{button_style} background-color: #27ae60;",
+                        disabled: is_running() || split_source().is_none(),
+                        onclick: run_split,
+                        "分割して保存…"
+                    }
+                }
+
+                // 進捗と結果
+                if let Some((done, total)) = progress() {
+                    div {
+                        style: "margin-bottom: 8px;",
+                        div {
+                            style: "height: 8px; background-color: #34495e; border-radius: 4px; overflow: hidden;",
+                            div {
+                                style: format!(
+                                    "height: 100%; background-color: #3498db; width: {}%;",
+                                    if total == 0 { 100.0 } else { done as f32 * 100.0 / total as f32 }
+                                ),
+                            }
+                        }
+                        div {
+                            style: "font-size: 12px; color: #95a5a6; margin-top: 4px;",
+                            "{done} / {total} ページ"
+                        }
+                    }
+                }
+                if !status().is_empty() {
+                    div {
+                        style: "font-size: 13px; color: #ecf0f1; word-break: break-all;",
+                        "{status}"
+                    }
+                }
+            }
+        }
+    }
+}
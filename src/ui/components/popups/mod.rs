@@ -2,8 +2,16 @@ pub mod flashcards;
 pub mod bookmarks;
 pub mod markers;
 pub mod recent_files;
+pub mod properties;
+pub mod password;
+pub mod library_search;
+pub mod merge_split;
 
 pub use flashcards::*;
 pub use bookmarks::*;
 pub use markers::*;
-pub use recent_files::*;
\ No newline at end of file
+pub use recent_files::*;
+pub use properties::*;
+pub use password::*;
+pub use library_search::*;
+pub use merge_split::*;
\ No newline at end of file
@@ -0,0 +1,85 @@
+use dioxus::prelude::*;
+use std::path::PathBuf;
+use crate::set_document_password;
+
+#[component]
+pub fn password_prompt_popup(
+    pdf_path: Signal<Option<PathBuf>>,
+    password_attempts: Signal<u32>,
+    loaded_pdf_path: Signal<Option<PathBuf>>,
+) -> Element {
+    let mut password_input = use_signal(|| String::new());
+    let mut has_submitted = use_signal(|| false); // 一度入力した後も表示されている場合はパスワードの誤り
+    
+    let mut submit_password = move || {
+        if let Some(path) = pdf_path() {
+            set_document_password(&path.to_string_lossy(), password_input());
+            password_input.set(String::new());
+            has_submitted.set(true);
+            password_attempts += 1;
+        }
+    };
+    
+    rsx! {
+        div { 
+            class: "popup-overlay",
+            style: "position: fixed; top: 0; left: 0; width: 100%; height: 100%; background-color: rgba(0, 0, 0, 0.7); display: flex; align-items: center; justify-content: center; z-index: 1000;",
+            div { 
+                class: "popup-content",
+                style: "background-color: #2c3e50; border-radius: 8px; padding: 20px; width: 400px; position: relative;",
+                onclick: move |e| {
+                    e.stop_propagation();
+                },
+                
+                h2 { 
+                    style: "color: #ecf0f1; margin: 0 0 15px 0; font-size: 18px; border-bottom: 1px solid #34495e; padding-bottom: 10px;",
+                    "🔒 パスワードが必要です"
+                }
+                p {
+                    style: "color: #bdc3c7; font-size: 14px; word-break: break-all;",
+                    {
+                        let file_name = pdf_path()
+                            .and_then(|path| path.file_name().map(|name| name.to_string_lossy().to_string()))
+                            .unwrap_or_default();
+                        format!("「{}」を開くにはパスワードを入力してください。", file_name)
+                    }
+                }
+                if has_submitted() {
+                    p {
+                        style: "color: #e74c3c; font-size: 14px;",
+                        "パスワードが正しくありません。"
+                    }
+                }
+                input {
+                    r#type: "password",
+                    value: "{password_input}",
+                    autofocus: true,
+                    placeholder: "パスワード",
+                    style: "width: 100%; box-sizing: border-box; padding: 8px; border-radius: 4px; border: 1px solid #bdc3c7; margin-bottom: 15px;",
+                    oninput: move |evt| password_input.set(evt.value()),
+                    onkeydown: move |evt| {
+                        if evt.key() == Key::Enter {
+                            submit_password();
+                        }
+                    },
+                }
+                div {
+                    style: "display: flex; justify-content: flex-end; gap: 10px;",
+                    button {
+                        style: "padding: 8px 16px; background-color: #7f8c8d; color: white; border: none; border-radius: 4px; cursor: pointer;",
+                        onclick: move |_| {
+                            pdf_path.set(None);
+                            loaded_pdf_path.set(None);
+                        },
+                        "キャンセル"
+                    }
+                    button {
+                        style: "padding: 8px 16px; background-color: #3498db; color: white; border: none; border-radius: 4px; cursor: pointer;",
+                        onclick: move |_| submit_password(),
+                        "開く"
+                    }
+                }
+            }
+        }
+    }
+}
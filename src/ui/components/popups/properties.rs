@@ -0,0 +1,118 @@
+use dioxus::prelude::*;
+use std::path::PathBuf;
+use crate::{DocumentInfo, DocumentLoadError};
+
+#[component]
+pub fn document_properties_popup(
+    show_properties_popup: Signal<bool>,
+    document_info: Memo<Result<DocumentInfo, DocumentLoadError>>,
+    pdf_path: Signal<Option<PathBuf>>,
+) -> Element {
+    rsx! {
+        div { 
+            class: "popup-overlay",
+            style: "position: fixed; top: 0; left: 0; width: 100%; height: 100%; background-color: rgba(0, 0, 0, 0.7); display: flex; align-items: center; justify-content: center; z-index: 1000;",
+            onclick: move |_| {
+                show_properties_popup.set(false);
+            },
+            div { 
+                class: "popup-content",
+                style: "background-color: #2c3e50; border-radius: 8px; padding: 20px; min-width: 450px; max-width: 600px; max-height: 80vh; overflow-y: auto; position: relative;",
+                onclick: move |e| {
+                    e.stop_propagation();
+                },
+                
+                // ヘッダー
+                div { 
+                    style: "display: flex; justify-content: space-between; align-items: center; margin-bottom: 20px; border-bottom: 1px solid #34495e; padding-bottom: 10px;",
+                    h2 { 
+                        style: "color: #ecf0f1; margin: 0; font-size: 18px;",
+                        "ℹ️ 文書のプロパティ"
+                    }
+                    button { 
+                        style: "background: none; border: none; color: #e74c3c; cursor: pointer; font-size: 24px; padding: 0;",
+                        onclick: move |_| {
+                            show_properties_popup.set(false);
+                        },
+                        "×"
+                    }
+                }
+                
+                match document_info() {
+                    Ok(info) => {
+                        let file_path = pdf_path().map(|path| path.to_string_lossy().to_string()).unwrap_or_default();
+                        let rows = vec![
+                            ("タイトル", info.title.clone()),
+                            ("作成者", info.author.clone()),
+                            ("サブタイトル", info.subject.clone()),
+                            ("キーワード", info.keywords.clone()),
+                            ("作成アプリケーション", info.creator.clone()),
+                            ("PDF変換", info.producer.clone()),
+                            ("作成日時", info.creation_date.clone()),
+                            ("更新日時", info.modification_date.clone()),
+                            ("PDFバージョン", Some(info.pdf_version.clone())),
+                            ("ページ数", Some(format!("{}ページ", info.page_count))),
+                            ("ファイルサイズ", Some(format_file_size(info.file_size))),
+                            ("場所", Some(file_path)),
+                            ("セキュリティ", Some(match (info.is_encrypted, info.requires_password) {
+                                (_, true) => "🔒 パスワードで保護（このセッションで解除済み）".to_string(),
+                                (true, false) => "🔒 暗号化（権限パスワードのみ）".to_string(),
+                                (false, false) => "🔓 なし".to_string(),
+                            })),
+                            ("印刷", Some(permission_label(info.permissions.can_print))),
+                            ("テキストのコピー", Some(permission_label(info.permissions.can_copy))),
+                            ("内容の変更", Some(permission_label(info.permissions.can_modify))),
+                            ("注釈の追加", Some(permission_label(info.permissions.can_annotate))),
+                        ];
+                        rsx! {
+                            table {
+                                style: "width: 100%; border-collapse: collapse; color: #ecf0f1; font-size: 14px;",
+                                for (label, value) in rows {
+                                    tr {
+                                        key: "{label}",
+                                        style: "border-bottom: 1px solid #34495e;",
+                                        td {
+                                            style: "padding: 8px 12px 8px 0; color: #95a5a6; white-space: nowrap; vertical-align: top;",
+                                            "{label}"
+                                        }
+                                        td {
+                                            style: "padding: 8px 0; word-break: break-all;",
+                                            {value.unwrap_or_else(|| "—".to_string())}
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    Err(error) => rsx! {
+                        div { 
+                            style: "text-align: center; padding: 40px; color: #bdc3c7; font-size: 16px;",
+                            "{error.message()}"
+                        }
+                    },
+                }
+            }
+        }
+    }
+}
+
+fn permission_label(allowed: bool) -> String {
+    if allowed { "✅ 許可" } else { "🚫 禁止" }.to_string()
+}
+
+// バイト数を読みやすい単位に変換
+fn format_file_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[unit])
+    } else {
+        format!("{:.1} {} ({} バイト)", size, UNITS[unit], bytes)
+    }
+}
@@ -1,7 +1,6 @@
 use dioxus::prelude::*;
 use std::path::PathBuf;
-use std::collections::HashMap;
-use crate::{RecentFile, load_recent_files, add_recent_file, PdfPageData};
+use crate::{RecentFile, load_recent_files, add_recent_file, release_pdf_document, PageCache};
 
 #[component]
 pub fn recent_files_popup(
@@ -9,7 +8,7 @@ pub fn recent_files_popup(
     recent_files_list: Memo<Vec<RecentFile>>,
     recent_files: Signal<Vec<RecentFile>>,
     pdf_path: Signal<Option<PathBuf>>,
-    page_cache: Signal<HashMap<usize, PdfPageData>>,
+    page_cache: Signal<PageCache>,
     loaded_pdf_path: Signal<Option<PathBuf>>,
     is_loading: Signal<bool>,
 ) -> Element {
@@ -68,6 +67,9 @@ pub fn recent_files_popup(
                                                 .to_string();
                                             let _ = add_recent_file(file_path.to_string_lossy().to_string(), file_name);
                                             recent_files.set(load_recent_files());
+                                            if let Some(previous_path) = pdf_path() {
+                                                release_pdf_document(&previous_path.to_string_lossy());
+                                            }
                                             pdf_path.set(Some(file_path.clone()));
                                             page_cache.write().clear();
                                             loaded_pdf_path.set(None);
@@ -0,0 +1,258 @@
+use dioxus::prelude::*;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use crate::{RotationAngle, SearchMatch, SearchOptions, TextElement, extract_document_text_in_background, search_document};
+
+// 一致箇所のページまでスクロールし、一致した矩形が画面の中央付近に来るようにする
+// 一致箇所の矩形とページの寸法は回転前のものなので、表示上（回転後）の位置に変換して使う
+fn scroll_to_match(search_match: &SearchMatch, page_width: f32, page_height: f32, rotation: RotationAngle) {
+    let displayed_height = match rotation {
+        RotationAngle::Rotate90 | RotationAngle::Rotate270 => page_width,
+        _ => page_height,
+    };
+    let fraction = search_match.rects.first().map_or(0.0, |rect| {
+        let rotated = rect.rotated(rotation, page_width, page_height);
+        (rotated.y / displayed_height).clamp(0.0, 1.0)
+    });
+    eval(&format!(
+        r#"
+        const viewer = document.querySelector('.pdf-viewer');
+        const element = document.getElementById('page-wrapper-{}');
+        if (viewer && element) {{
+            const rect = element.getBoundingClientRect();
+            const viewerRect = viewer.getBoundingClientRect();
+            viewer.scrollBy({{ top: rect.top - viewerRect.top + rect.height * {} - viewerRect.height / 3, behavior: 'smooth' }});
+        }}
+        "#,
+        search_match.page_index, fraction
+    ));
+}
+
+#[component]
+pub fn document_search_panel(
+    loaded_pdf_path: Signal<Option<PathBuf>>,
+    render_generation: Signal<u64>,
+    page_sizes: Memo<Vec<(f32, f32)>>,
+    page_rotations: Signal<HashMap<usize, RotationAngle>>,
+    search_matches: Signal<Vec<SearchMatch>>,
+    active_match: Signal<Option<usize>>,
+) -> Element {
+    let mut query = use_signal(|| String::new());
+    let mut options = use_signal(SearchOptions::default);
+    let mut document_text = use_signal(|| -> Option<Vec<Vec<TextElement>>> { None });
+    let mut is_searching = use_signal(|| false);
+    let mut search_error = use_signal(|| String::new());
+
+    // 別のファイルを開いたら抽出済みのテキストと検索結果を破棄
+    use_effect(move || {
+        let _ = render_generation();
+        document_text.set(None);
+        search_matches.set(Vec::new());
+        active_match.set(None);
+    });
+
+    let mut select_match = move |index: usize| {
+        if let Some(search_match) = search_matches.peek().get(index) {
+            let (page_width, page_height) = page_sizes.peek().get(search_match.page_index).copied().unwrap_or((612.0, 792.0));
+            let rotation = page_rotations.peek().get(&search_match.page_index).copied().unwrap_or(RotationAngle::None);
+            scroll_to_match(search_match, page_width, page_height, rotation);
+        }
+        active_match.set(Some(index));
+    };
+
+    let mut run_search = move || {
+        let query_text = query();
+        let search_options = options();
+        let Some(path) = loaded_pdf_path() else { return };
+        search_error.set(String::new());
+
+        if query_text.is_empty() {
+            search_matches.set(Vec::new());
+            active_match.set(None);
+            return;
+        }
+
+        spawn(async move {
+            // 初回の検索時に全ページのテキストを抽出し、以降は使い回す
+            if document_text.peek().is_none() {
+                is_searching.set(true);
+                let generation = render_generation();
+                let result = extract_document_text_in_background(&path.to_string_lossy(), generation).await;
+                is_searching.set(false);
+                if *render_generation.peek() != generation {
+                    return;
+                }
+                match result {
+                    Ok(pages) => document_text.set(Some(pages)),
+                    Err(e) => {
+                        search_error.set(format!("テキストの抽出に失敗しました: {}", e));
+                        return;
+                    }
+                }
+            }
+
+            // テキストの抽出中に条件が変わった場合は、古い条件の結果を表示しない
+            if *query.peek() != query_text || *options.peek() != search_options {
+                return;
+            }
+
+            let result = match document_text.peek().as_ref() {
+                Some(pages) => search_document(pages, &query_text, &search_options),
+                None => return,
+            };
+            match result {
+                Ok(matches) => {
+                    let has_matches = !matches.is_empty();
+                    search_matches.set(matches);
+                    active_match.set(None);
+                    if has_matches {
+                        select_match(0);
+                    }
+                }
+                Err(e) => {
+                    search_matches.set(Vec::new());
+                    active_match.set(None);
+                    search_error.set(format!("検索パターンが正しくありません: {}", e));
+                }
+            }
+        });
+    };
+
+    let mut move_match = move |forward: bool| {
+        let count = search_matches.peek().len();
+        if count == 0 {
+            return;
+        }
+        let next = match *active_match.peek() {
+            Some(current) if forward => (current + 1) % count,
+            Some(current) => (current + count - 1) % count,
+            None => 0,
+        };
+        select_match(next);
+    };
+
+    rsx! {
+        div {
+            class: "document-search-panel",
+            style: "margin-bottom: 10px; padding: 10px; background-color: #f8f9fa; border-radius: 4px; display: flex; flex-direction: column; gap: 8px;",
+            div {
+                style: "display: flex; align-items: center; gap: 8px; flex-wrap: wrap;",
+                input {
+                    r#type: "text",
+                    value: "{query}",
+                    placeholder: "文書内を検索",
+                    style: "flex: 1; min-width: 150px; padding: 5px 8px; border-radius: 3px; border: 1px solid #bdc3c7;",
+                    oninput: move |evt| {
+                        // 条件が変わったら前回の結果は使わない（Enterで新しい条件の検索をやり直す）
+                        query.set(evt.value());
+                        search_matches.set(Vec::new());
+                        active_match.set(None);
+                    },
+                    onkeydown: move |evt| {
+                        if evt.key() == Key::Enter {
+                            // 同じ条件で検索済みなら次の一致箇所へ（Shift+Enterで前へ）
+                            if search_matches.peek().is_empty() {
+                                run_search();
+                            } else {
+                                move_match(!evt.modifiers().shift());
+                            }
+                        }
+                    },
+                }
+                button {
+                    style: "padding: 5px 10px; background-color: #3498db; color: white; border: none; border-radius: 3px; cursor: pointer;",
+                    onclick: move |_| run_search(),
+                    "🔍 検索"
+                }
+                button {
+                    style: "padding: 5px 10px; background-color: #34495e; color: white; border: none; border-radius: 3px; cursor: pointer;",
+                    disabled: search_matches.read().is_empty(),
+                    onclick: move |_| move_match(false),
+                    "▲ 前へ"
+                }
+                button {
+                    style: "padding: 5px 10px; background-color: #34495e; color: white; border: none; border-radius: 3px; cursor: pointer;",
+                    disabled: search_matches.read().is_empty(),
+                    onclick: move |_| move_match(true),
+                    "▼ 次へ"
+                }
+                span {
+                    style: "font-size: 12px; color: #7f8c8d;",
+                    if is_searching() {
+                        "テキストを抽出中..."
+                    } else if !search_matches.read().is_empty() {
+                        {format!("{} / {}件", active_match().map_or(0, |index| index + 1), search_matches.read().len())}
+                    } else if !query().is_empty() && document_text.read().is_some() {
+                        "一致なし"
+                    }
+                }
+            }
+            div {
+                style: "display: flex; gap: 15px; font-size: 12px; color: #2c3e50;",
+                label {
+                    input {
+                        r#type: "checkbox",
+                        checked: options().case_sensitive,
+                        onchange: move |evt| {
+                            options.write().case_sensitive = evt.checked();
+                            search_matches.set(Vec::new());
+                            active_match.set(None);
+                        },
+                    }
+                    " 大文字と小文字を区別"
+                }
+                label {
+                    input {
+                        r#type: "checkbox",
+                        checked: options().whole_word,
+                        onchange: move |evt| {
+                            options.write().whole_word = evt.checked();
+                            search_matches.set(Vec::new());
+                            active_match.set(None);
+                        },
+                    }
+                    " 単語単位"
+                }
+                label {
+                    input {
+                        r#type: "checkbox",
+                        checked: options().use_regex,
+                        onchange: move |evt| {
+                            options.write().use_regex = evt.checked();
+                            search_matches.set(Vec::new());
+                            active_match.set(None);
+                        },
+                    }
+                    " 正規表現"
+                }
+            }
+            if !search_error().is_empty() {
+                div {
+                    style: "font-size: 12px; color: #721c24;",
+                    "{search_error}"
+                }
+            }
+            if !search_matches.read().is_empty() {
+                div {
+                    class: "search-results",
+                    style: "max-height: 160px; overflow-y: auto; border-top: 1px solid #dee2e6;",
+                    for (match_idx, search_match) in search_matches.read().iter().enumerate() {
+                        div {
+                            key: "{match_idx}",
+                            style: format!(
+                                "padding: 4px 6px; font-size: 12px; cursor: pointer; border-bottom: 1px solid #ecf0f1; background-color: {};",
+                                if active_match() == Some(match_idx) { "#fdebd0" } else { "transparent" }
+                            ),
+                            onclick: move |_| select_match(match_idx),
+                            span {
+                                style: "color: #3498db; font-weight: bold; margin-right: 8px;",
+                                "p.{search_match.page_index + 1}"
+                            }
+                            "{search_match.snippet}"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use dioxus::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use crate::{PageImage, PositionMarker, ReadingBookmark, RotationAngle, render_thumbnail_in_background};
+
+// 画面外のサムネイルを保持しておく上限（超えた分は見えている位置から遠いものから破棄）
+const MAX_CACHED_THUMBNAILS: usize = 200;
+
+#[component]
+pub fn thumbnail_sidebar(
+    loaded_pdf_path: Signal<Option<PathBuf>>,
+    total_pages: usize,
+    current_page: Signal<usize>,
+    page_sizes: Memo<Vec<(f32, f32)>>,
+    page_rotations: Signal<HashMap<usize, RotationAngle>>,
+    current_bookmark: Signal<Option<ReadingBookmark>>,
+    position_markers: Signal<Vec<PositionMarker>>,
+    render_generation: Signal<u64>,
+) -> Element {
+    let mut thumbnails = use_signal(|| HashMap::<usize, (RotationAngle, PageImage)>::new());
+    let mut requested_thumbnails = use_signal(|| HashSet::<(usize, RotationAngle)>::new());
+    let mut visible_thumbnails = use_signal(|| Vec::<usize>::new());
+
+    // サイドバーに見えているサムネイルを検出（見えているものだけをレンダリングする）
+    use_future(move || async move {
+        let mut visible_eval = eval(r#"
+            let scheduled = false;
+            const report = () => {
+                scheduled = false;
+                const sidebar = document.querySelector('.thumbnail-sidebar');
+                if (!sidebar) return;
+                const sidebarRect = sidebar.getBoundingClientRect();
+                // 少し先のサムネイルまで読み込んでおく
+                const margin = sidebarRect.height / 2;
+                const visible = [];
+                for (const element of sidebar.querySelectorAll('[id^="thumbnail-"]')) {
+                    const rect = element.getBoundingClientRect();
+                    if (rect.bottom > sidebarRect.top - margin && rect.top < sidebarRect.bottom + margin) {
+                        visible.push(parseInt(element.id.substring('thumbnail-'.length)));
+                    }
+                }
+                dioxus.send({ visible: visible });
+            };
+            const scheduleReport = () => {
+                if (!scheduled) {
+                    scheduled = true;
+                    requestAnimationFrame(report);
+                }
+            };
+            document.addEventListener('scroll', scheduleReport, true);
+            window.addEventListener('resize', scheduleReport);
+            new MutationObserver(scheduleReport).observe(document.body, { childList: true, subtree: true });
+            scheduleReport();
+        "#);
+
+        while let Ok(value) = visible_eval.recv().await {
+            if let Some(visible) = value.get("visible").and_then(|visible| visible.as_array()) {
+                let visible: Vec<usize> = visible.iter().filter_map(|v| v.as_u64()).map(|v| v as usize).collect();
+                if *visible_thumbnails.peek() != visible {
+                    visible_thumbnails.set(visible);
+                }
+            }
+        }
+    });
+
+    // 別のファイルを開いたらサムネイルを破棄
+    use_effect(move || {
+        let _ = render_generation();
+        thumbnails.write().clear();
+        requested_thumbnails.write().clear();
+    });
+
+    // 見えているサムネイルのうち未レンダリングのものを要求
+    use_effect(move || {
+        let generation = render_generation();
+        let visible = visible_thumbnails();
+        let rotations = page_rotations();
+        let Some(path) = loaded_pdf_path() else { return };
+
+        for &page_idx in visible.iter() {
+            let rotation = rotations.get(&page_idx).copied().unwrap_or(RotationAngle::None);
+            let is_cached = thumbnails.peek().get(&page_idx).map_or(false, |(cached_rotation, _)| *cached_rotation == rotation);
+            if is_cached || requested_thumbnails.peek().contains(&(page_idx, rotation)) {
+                continue;
+            }
+
+            requested_thumbnails.write().insert((page_idx, rotation));
+            let path_str = path.to_string_lossy().to_string();
+            spawn(async move {
+                let result = render_thumbnail_in_background(&path_str, page_idx, rotation, generation).await;
+                if *render_generation.peek() != generation {
+                    return;
+                }
+                requested_thumbnails.write().remove(&(page_idx, rotation));
+                if let Ok(thumbnail) = result {
+                    thumbnails.write().insert(page_idx, (rotation, thumbnail));
+                }
+            });
+        }
+
+        // 上限を超えた分は、見えている位置から遠いサムネイルから破棄
+        let cached_count = thumbnails.peek().len();
+        if cached_count > MAX_CACHED_THUMBNAILS {
+            let anchor = visible.first().copied().unwrap_or(0);
+            let mut cached_pages: Vec<usize> = thumbnails.peek().keys().copied().filter(|page_idx| !visible.contains(page_idx)).collect();
+            cached_pages.sort_by_key(|page_idx| std::cmp::Reverse(page_idx.abs_diff(anchor)));
+            let mut thumbnails = thumbnails.write();
+            for page_idx in cached_pages.into_iter().take(cached_count - MAX_CACHED_THUMBNAILS) {
+                thumbnails.remove(&page_idx);
+            }
+        }
+    });
+
+    // 表示中のページが変わったら、そのサムネイルが見えるようにサイドバーをスクロール
+    use_effect(move || {
+        let page_idx = current_page();
+        eval(&format!(
+            r#"
+            const element = document.getElementById('thumbnail-{}');
+            if (element) {{
+                element.scrollIntoView({{ block: 'nearest' }});
+            }}
+            "#,
+            page_idx
+        ));
+    });
+
+    // マーカーが置かれているページごとの件数
+    let marker_counts = {
+        let mut counts = HashMap::<usize, usize>::new();
+        for marker in position_markers().iter() {
+            *counts.entry(marker.page_index).or_insert(0) += 1;
+        }
+        counts
+    };
+    let bookmarked_page = current_bookmark().map(|bookmark| bookmark.current_page);
+
+    rsx! {
+        div {
+            class: "thumbnail-sidebar",
+            style: "width: 150px; flex-shrink: 0; overflow-y: auto; background-color: #34495e; padding: 10px; display: flex; flex-direction: column; gap: 10px; height: 100%; max-height: calc(100vh - 200px);",
+            for page_idx in 0..total_pages {
+                div {
+                    key: "{page_idx}",
+                    id: "thumbnail-{page_idx}",
+                    class: "thumbnail-item",
+                    style: format!(
+                        "position: relative; cursor: pointer; border: 3px solid {}; border-radius: 4px; background-color: white;",
+                        if current_page() == page_idx { "#3498db" } else { "transparent" }
+                    ),
+                    title: "クリックしてページ {page_idx + 1} に移動",
+                    onclick: move |_| {
+                        eval(&format!(
+                            r#"
+                            const element = document.getElementById('page-wrapper-{}');
+                            if (element) {{
+                                element.scrollIntoView({{ behavior: 'smooth', block: 'start' }});
+                            }}
+                            "#,
+                            page_idx
+                        ));
+                    },
+                    {
+                        let (width, height) = page_sizes.read().get(page_idx).copied().unwrap_or((612.0, 792.0));
+                        let (width, height) = match page_rotations.read().get(&page_idx).copied().unwrap_or(RotationAngle::None) {
+                            RotationAngle::Rotate90 | RotationAngle::Rotate270 => (height, width),
+                            _ => (width, height),
+                        };
+                        let thumbnail = thumbnails.read().get(&page_idx).map(|(_, thumbnail)| thumbnail.url());
+                        rsx! {
+                            div {
+                                style: "width: 100%; aspect-ratio: {width} / {height}; display: flex; align-items: center; justify-content: center; color: #bdc3c7; font-size: 12px;",
+                                if let Some(url) = thumbnail {
+                                    img {
+                                        src: "{url}",
+                                        alt: "Page {page_idx + 1}",
+                                        style: "display: block; width: 100%; height: 100%;",
+                                    }
+                                } else {
+                                    "…"
+                                }
+                            }
+                        }
+                    }
+                    div {
+                        style: "position: absolute; top: 2px; right: 2px; display: flex; gap: 2px; font-size: 12px;",
+                        if bookmarked_page == Some(page_idx) {
+                            span { title: "ブックマーク", "🔖" }
+                        }
+                        if let Some(count) = marker_counts.get(&page_idx) {
+                            span {
+                                title: "位置マーカー",
+                                style: "background-color: #f39c12; color: white; border-radius: 8px; padding: 0 4px;",
+                                "📍{count}"
+                            }
+                        }
+                    }
+                    div {
+                        style: "text-align: center; font-size: 11px; color: #2c3e50; background-color: #ecf0f1; padding: 2px 0;",
+                        "{page_idx + 1}"
+                    }
+                }
+            }
+        }
+    }
+}